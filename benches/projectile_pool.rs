@@ -0,0 +1,150 @@
+// Compares spawning and despawning a projectile per shot against recycling projectiles
+// through the game's `ProjectilePool`, at a few thousand shots per second.
+//
+// Run with `cargo bench --bench projectile_pool`
+
+use bevy::prelude::*;
+use bevy_prototyping::asset_loader::ImageAssets;
+use bevy_prototyping::movement::MovementPlugin;
+use bevy_prototyping::projectile::{
+    fire_projectile, spawn_projectile, Projectile, ProjectilePlugin, ProjectilePool,
+    ProjectileQuery,
+};
+use bevy_prototyping::schedule::{InGameSet, SchedulePlugin, SIMULATION_HZ};
+use bevy_prototyping::state::GameState;
+use bevy_prototyping::world_bounds::{OnLeftWorldBounds, WorldBounds};
+use std::time::{Duration, Instant};
+
+const SIMULATED_SECONDS: u32 = 20;
+// Long enough for the first projectiles to expire, so both approaches are measured in a
+// steady state
+const WARM_UP_SECONDS: u32 = 3;
+const SHOTS_PER_SECOND: [u32; 3] = [1_000, 5_000, 10_000];
+const PROJECTILE_VELOCITY: Vec3 = Vec3::new(0.0, 500.0, 0.0);
+
+#[derive(Resource)]
+struct ShotsPerTick(u32);
+
+fn spawn_shots(mut commands: Commands, shots: Res<ShotsPerTick>, image_assets: Res<ImageAssets>) {
+    for _ in 0..shots.0 {
+        spawn_projectile(
+            &mut commands,
+            &image_assets,
+            Transform::default(),
+            PROJECTILE_VELOCITY,
+            true,
+        );
+    }
+}
+
+fn despawn_expired(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Projectile)>,
+    time: Res<Time>,
+) {
+    for (entity, mut projectile) in query.iter_mut() {
+        projectile.despawn_timer.tick(time.delta());
+
+        if projectile.despawn_timer.just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn fire_pooled_shots(
+    mut commands: Commands,
+    shots: Res<ShotsPerTick>,
+    mut pool: ResMut<ProjectilePool>,
+    mut projectiles: ProjectileQuery,
+    image_assets: Res<ImageAssets>,
+) {
+    for _ in 0..shots.0 {
+        fire_projectile(
+            &mut commands,
+            &mut pool,
+            &mut projectiles,
+            &image_assets,
+            Vec3::ZERO,
+            PROJECTILE_VELOCITY,
+        );
+    }
+}
+
+// The fixed-step simulation with nothing but movement, stepped by hand
+fn simulation_app(shots_per_tick: u32) -> App {
+    let mut app: App = App::new();
+    app.add_state::<GameState>()
+        .insert_resource(ShotsPerTick(shots_per_tick))
+        .init_resource::<ImageAssets>()
+        .init_resource::<WorldBounds>()
+        .add_event::<OnLeftWorldBounds>()
+        .add_plugins((SchedulePlugin, MovementPlugin));
+
+    let mut time: Time = Time::default();
+    time.advance_by(Duration::from_secs_f64(1.0 / SIMULATION_HZ));
+    app.insert_resource(time);
+
+    return app;
+}
+
+fn run_spawn_despawn(shots_per_tick: u32) -> Duration {
+    let mut app: App = simulation_app(shots_per_tick);
+    app.add_systems(
+        FixedUpdate,
+        (
+            despawn_expired.in_set(InGameSet::DespawnEntities),
+            spawn_shots.in_set(InGameSet::UserInput),
+        ),
+    );
+
+    return run_session(&mut app);
+}
+
+fn run_pooled(shots_per_tick: u32) -> Duration {
+    let mut app: App = simulation_app(shots_per_tick);
+    app.add_plugins(ProjectilePlugin)
+        .add_systems(FixedUpdate, fire_pooled_shots.in_set(InGameSet::UserInput));
+
+    return run_session(&mut app);
+}
+
+fn run_session(app: &mut App) -> Duration {
+    // Fills the projectile pool when there is one
+    app.world.run_schedule(OnEnter(GameState::LoadingGame));
+    app.insert_resource(State::new(GameState::InGame));
+
+    for _ in 0..WARM_UP_SECONDS * SIMULATION_HZ as u32 {
+        app.world.run_schedule(FixedUpdate);
+    }
+
+    let start: Instant = Instant::now();
+
+    for _ in 0..SIMULATED_SECONDS * SIMULATION_HZ as u32 {
+        app.world.run_schedule(FixedUpdate);
+    }
+
+    return start.elapsed();
+}
+
+fn main() {
+    let ticks: u32 = SIMULATED_SECONDS * SIMULATION_HZ as u32;
+
+    for shots_per_second in SHOTS_PER_SECOND {
+        let shots_per_tick: u32 = shots_per_second / SIMULATION_HZ as u32;
+
+        let spawn_despawn: Duration = run_spawn_despawn(shots_per_tick);
+        let pooled: Duration = run_pooled(shots_per_tick);
+
+        println!("{} shots/s over {} ticks", shots_per_second, ticks);
+        println!(
+            "  spawn/despawn: {:?} total, {:?} per tick",
+            spawn_despawn,
+            spawn_despawn / ticks
+        );
+        println!(
+            "  pooled:        {:?} total, {:?} per tick",
+            pooled,
+            pooled / ticks
+        );
+    }
+}
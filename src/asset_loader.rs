@@ -1,5 +1,12 @@
+use crate::state::GameState;
+use bevy::asset::{LoadState, UntypedAssetId};
 use bevy::prelude::*;
 
+// A resource holding handles to assets that must be loaded before the game starts
+pub trait AssetCollection: Resource {
+    fn ids(&self) -> Vec<UntypedAssetId>;
+}
+
 #[derive(Resource, Debug, Default)]
 pub struct ImageAssets {
     pub player: Handle<Image>,
@@ -7,12 +14,52 @@ pub struct ImageAssets {
     pub projectile: Handle<Image>,
 }
 
+impl AssetCollection for ImageAssets {
+    fn ids(&self) -> Vec<UntypedAssetId> {
+        return vec![
+            self.player.id().untyped(),
+            self.enemy.id().untyped(),
+            self.projectile.id().untyped(),
+        ];
+    }
+}
+
+// Load state of every tracked asset, rebuilt each frame while loading
+#[derive(Resource, Debug, Default)]
+pub struct LoadingProgress {
+    pub loaded: usize,
+    pub total: usize,
+    // paths of assets that failed to load
+    pub failed: Vec<String>,
+}
+
+impl LoadingProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+
+        return self.loaded as f32 / self.total as f32;
+    }
+}
+
 pub struct AssetLoaderPlugin;
 
 impl Plugin for AssetLoaderPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ImageAssets>()
-            .add_systems(Startup, load_assets);
+            .init_resource::<LoadingProgress>()
+            .add_systems(Startup, load_assets)
+            .add_systems(
+                Update,
+                (
+                    reset_loading_progress,
+                    track_loading::<ImageAssets>,
+                    finish_loading,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::LoadingGame)),
+            );
     }
 }
 
@@ -23,3 +70,38 @@ fn load_assets(mut image_assets: ResMut<ImageAssets>, asset_server: Res<AssetSer
         projectile: asset_server.load("circle.png"),
     }
 }
+
+fn reset_loading_progress(mut progress: ResMut<LoadingProgress>) {
+    *progress = LoadingProgress::default();
+}
+
+fn track_loading<T: AssetCollection>(
+    collection: Res<T>,
+    asset_server: Res<AssetServer>,
+    mut progress: ResMut<LoadingProgress>,
+) {
+    for id in collection.ids() {
+        progress.total += 1;
+
+        match asset_server.get_load_state(id) {
+            Some(LoadState::Loaded) => progress.loaded += 1,
+            Some(LoadState::Failed) => {
+                let path: String = asset_server
+                    .get_path(id)
+                    .map(|path| path.to_string())
+                    .unwrap_or_else(|| format!("{:?}", id));
+                progress.failed.push(path);
+            }
+            _ => (),
+        }
+    }
+}
+
+fn finish_loading(progress: Res<LoadingProgress>, mut next_state: ResMut<NextState<GameState>>) {
+    if !progress.failed.is_empty() {
+        error!("Failed to load assets: {:?}", progress.failed);
+        next_state.set(GameState::LoadingFailed);
+    } else if progress.loaded == progress.total {
+        next_state.set(GameState::InGame);
+    }
+}
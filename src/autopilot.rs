@@ -0,0 +1,274 @@
+use crate::cursor::{CursorSet, CursorWorldPosition};
+use crate::docking::{Docked, DockingPort};
+use crate::input::{ActionState, InputAction, InputActionSet};
+use crate::level_manager::Station;
+use crate::movement::{AngularVelocity, MaxAngularSpeed, MaxSpeed, Velocity};
+use crate::player::{player_movement, Player, PlayerInput};
+use crate::schedule::InGameSet;
+use crate::state::GameState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Deceleration the autopilot plans its braking around, kept below the ship's reverse thrust
+const BRAKING_ACCELERATION: f32 = 150.0;
+// Velocity error that gives full thrust
+const THRUST_RESPONSE: f32 = 40.0;
+// Only thrust when pointing this close to the direction we want to accelerate in
+const AIM_TOLERANCE: f32 = 0.35;
+const TURN_RESPONSE: f32 = 6.0;
+const ARRIVAL_RADIUS: f32 = 20.0;
+const ARRIVAL_SPEED: f32 = 30.0;
+// Fraction of a docking port's radius and max speed to aim for when docking
+const DOCKING_MARGIN: f32 = 0.6;
+const ROUTE_COLOUR: Color = Color::rgba(0.3, 0.8, 1.0, 0.7);
+const WAYPOINT_RADIUS: f32 = 8.0;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Waypoint {
+    Position(Vec2),
+    Station(Entity),
+}
+
+// A waypoint set by the player, resolved to a `Waypoint` by the simulation
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WaypointOrder {
+    pub position: Vec2,
+    // add to the end of the route instead of replacing it
+    pub queue: bool,
+}
+
+#[derive(Component, Debug)]
+pub struct Autopilot {
+    pub route: VecDeque<Waypoint>,
+    pub dock_at_stations: bool,
+    // control outputs, used in place of player input while a route is set
+    pub thrust: f32,
+    pub turn: f32,
+}
+
+impl Default for Autopilot {
+    fn default() -> Self {
+        Self {
+            route: VecDeque::new(),
+            dock_at_stations: true,
+            thrust: 0.0,
+            turn: 0.0,
+        }
+    }
+}
+
+impl Autopilot {
+    pub fn is_engaged(&self) -> bool {
+        return !self.route.is_empty();
+    }
+}
+
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            read_waypoint_input
+                .after(CursorSet)
+                .after(InputActionSet)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedUpdate,
+            (update_route, steer_autopilot)
+                .chain()
+                .in_set(InGameSet::UserInput)
+                .before(player_movement),
+        )
+        .add_systems(Update, draw_route.run_if(in_state(GameState::InGame)));
+    }
+}
+
+fn read_waypoint_input(
+    actions: Res<ActionState>,
+    cursor: Res<CursorWorldPosition>,
+    mut player_input: ResMut<PlayerInput>,
+) {
+    if !actions.just_pressed(InputAction::SetWaypoint) {
+        return;
+    }
+
+    let Some(position) = cursor.position else {
+        return;
+    };
+
+    player_input.waypoint = Some(WaypointOrder {
+        position,
+        queue: actions.pressed(InputAction::QueueWaypoint),
+    });
+}
+
+fn update_route(
+    mut player_input: ResMut<PlayerInput>,
+    mut player_query: Query<&mut Autopilot, (With<Player>, Without<Docked>)>,
+    station_query: Query<(Entity, &Transform, &DockingPort), With<Station>>,
+) {
+    let Ok(mut autopilot) = player_query.get_single_mut() else {
+        player_input.waypoint = None;
+        return;
+    };
+
+    // Flying manually takes over from the autopilot
+    if autopilot.is_engaged() && player_input.is_steering() {
+        autopilot.route.clear();
+        info!("Autopilot disengaged");
+    }
+
+    let Some(order) = player_input.waypoint.take() else {
+        return;
+    };
+
+    // Orders on top of a station target the station itself
+    let mut waypoint: Waypoint = Waypoint::Position(order.position);
+
+    for (station, transform, port) in station_query.iter() {
+        if order.position.distance(transform.translation.truncate()) <= port.radius {
+            waypoint = Waypoint::Station(station);
+            break;
+        }
+    }
+
+    if !order.queue {
+        autopilot.route.clear();
+    }
+
+    autopilot.route.push_back(waypoint);
+    info!("Autopilot waypoint added: {:?}", waypoint);
+}
+
+fn steer_autopilot(
+    mut player_input: ResMut<PlayerInput>,
+    mut player_query: Query<
+        (
+            &Transform,
+            &Velocity,
+            &AngularVelocity,
+            &MaxSpeed,
+            &MaxAngularSpeed,
+            &mut Autopilot,
+        ),
+        With<Player>,
+    >,
+    station_query: Query<(&Transform, &DockingPort), With<Station>>,
+) {
+    let Ok((transform, velocity, angular_velocity, max_speed, max_angular_speed, mut autopilot)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    autopilot.thrust = 0.0;
+    autopilot.turn = 0.0;
+
+    let Some(waypoint) = autopilot.route.front().copied() else {
+        return;
+    };
+
+    // Where we're heading and how close we need to get
+    let (target, arrival_radius, arrival_speed, dock) = match waypoint {
+        Waypoint::Position(position) => (position, ARRIVAL_RADIUS, ARRIVAL_SPEED, false),
+        Waypoint::Station(station) => {
+            let Ok((station_transform, port)) = station_query.get(station) else {
+                // Station is gone, skip to the next waypoint
+                autopilot.route.pop_front();
+                return;
+            };
+
+            (
+                station_transform.translation.truncate(),
+                port.radius * DOCKING_MARGIN,
+                port.max_speed * DOCKING_MARGIN,
+                autopilot.dock_at_stations,
+            )
+        }
+    };
+
+    let position: Vec2 = transform.translation.truncate();
+    let current_velocity: Vec2 = velocity.value.truncate();
+    let to_target: Vec2 = target - position;
+    let distance: f32 = to_target.length();
+
+    if distance <= arrival_radius && current_velocity.length() <= arrival_speed {
+        autopilot.route.pop_front();
+
+        if dock {
+            player_input.dock = true;
+        }
+
+        return info!("Autopilot reached waypoint: {:?}", waypoint);
+    }
+
+    // Fastest speed we can still brake from before reaching the target
+    let braking_distance: f32 = (distance - arrival_radius).max(0.0);
+    let desired_speed: f32 = (2.0 * BRAKING_ACCELERATION * braking_distance)
+        .sqrt()
+        .min(max_speed.value);
+    let desired_velocity: Vec2 = to_target.normalize_or_zero() * desired_speed;
+    let velocity_error: Vec2 = desired_velocity - current_velocity;
+
+    let forward: Vec2 = transform.up().truncate();
+
+    // Point at the target, thrust forward to speed up and reverse thrust to slow down
+    let reverse: bool = forward.dot(velocity_error) < 0.0 && forward.dot(to_target) > 0.0;
+    let aim: Vec2 = if velocity_error.length() > ARRIVAL_SPEED {
+        if reverse {
+            -velocity_error
+        } else {
+            velocity_error
+        }
+    } else {
+        to_target
+    };
+
+    if aim == Vec2::ZERO {
+        return;
+    }
+
+    let angle: f32 = forward.angle_between(aim);
+
+    // turn towards the aim, easing off as the ship's rotation lines up
+    let desired_angular_velocity: f32 =
+        (angle * TURN_RESPONSE).clamp(-max_angular_speed.value, max_angular_speed.value);
+    autopilot.turn = (desired_angular_velocity - angular_velocity.value).clamp(-1.0, 1.0);
+
+    if angle.abs() < AIM_TOLERANCE {
+        let thrust: f32 = (velocity_error.length() / THRUST_RESPONSE).min(1.0);
+        autopilot.thrust = if reverse { -thrust } else { thrust };
+    }
+}
+
+fn draw_route(
+    mut gizmos: Gizmos,
+    player_query: Query<(&Transform, &Autopilot), With<Player>>,
+    station_query: Query<&Transform, With<Station>>,
+) {
+    let Ok((transform, autopilot)) = player_query.get_single() else {
+        return;
+    };
+
+    let mut from: Vec2 = transform.translation.truncate();
+
+    for waypoint in autopilot.route.iter() {
+        let to: Vec2 = match waypoint {
+            Waypoint::Position(position) => *position,
+            Waypoint::Station(station) => {
+                let Ok(station_transform) = station_query.get(*station) else {
+                    continue;
+                };
+
+                station_transform.translation.truncate()
+            }
+        };
+
+        gizmos.line_2d(from, to, ROUTE_COLOUR);
+        gizmos.circle_2d(to, WAYPOINT_RADIUS, ROUTE_COLOUR);
+        from = to;
+    }
+}
@@ -1,40 +1,184 @@
+use crate::movement::Velocity;
 use crate::player::Player;
-use crate::schedule::InGameSet;
+use crate::schedule::RenderSet;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
 
+// Pixels in one scroll line, so trackpad and mouse wheel scrolling zoom at the same rate
+const PIXELS_PER_SCROLL_LINE: f32 = 100.0;
+
 #[derive(Component, Debug)]
 pub struct MainCamera;
 
+// Smoothing state for the follow camera, effects like screen shake are applied on top of
+// `position` and `zoom` so they don't feed back into the follow
+#[derive(Component, Debug)]
+pub struct FollowCamera {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub zoom: f32,
+    pub target_zoom: f32,
+}
+
+#[derive(Resource, Debug)]
+pub struct CameraSettings {
+    // time in seconds for the camera to settle on its target, lower is snappier
+    pub smooth_time: f32,
+    // how many seconds of player velocity the camera looks ahead by
+    pub look_ahead_time: f32,
+    pub max_look_ahead: f32,
+    // the player can move this far from the camera's target before it follows
+    pub dead_zone: f32,
+    // the camera jumps straight to the player when further away than this, i.e. on spawn
+    pub snap_distance: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    // fraction the zoom changes by per scroll line
+    pub zoom_step: f32,
+    pub zoom_smooth_time: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            smooth_time: 0.25,
+            look_ahead_time: 0.4,
+            max_look_ahead: 150.0,
+            dead_zone: 20.0,
+            snap_distance: 1500.0,
+            min_zoom: 0.5,
+            max_zoom: 3.0,
+            zoom_step: 0.1,
+            zoom_smooth_time: 0.15,
+        }
+    }
+}
+
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup)
-            // Run after entities update to fix player jutter
+        app.init_resource::<CameraSettings>()
+            .add_systems(Startup, setup)
+            // Run after transforms are interpolated to fix player jutter
             // to stop player position being updated after camera moves
-            .add_systems(Update, sync_player_camera.after(InGameSet::EntityUpdates));
+            .add_systems(
+                Update,
+                (zoom_camera, sync_player_camera).in_set(RenderSet::CameraUpdates),
+            );
     }
 }
 
 fn setup(mut commands: Commands, _asset_server: Res<AssetServer>) {
-    commands.spawn((Camera2dBundle::default(), MainCamera));
+    commands.spawn((
+        Camera2dBundle::default(),
+        MainCamera,
+        FollowCamera {
+            position: Vec2::ZERO,
+            velocity: Vec2::ZERO,
+            zoom: 1.0,
+            target_zoom: 1.0,
+        },
+    ));
 }
 
 pub fn sync_player_camera(
-    player: Query<&Transform, With<Player>>,
-    mut camera: Query<&mut Transform, (With<MainCamera>, Without<Player>)>,
+    player: Query<(&Transform, &Velocity), With<Player>>,
+    mut camera: Query<(&mut Transform, &mut FollowCamera), (With<MainCamera>, Without<Player>)>,
+    settings: Res<CameraSettings>,
+    time: Res<Time>,
 ) {
-    let Ok(player) = player.get_single() else {
+    let Ok((player, velocity)) = player.get_single() else {
+        return;
+    };
+
+    let Ok((mut camera_transform, mut follow)) = camera.get_single_mut() else {
+        return;
+    };
+
+    let camera_position: Vec2 = follow.position;
+    let player_position: Vec2 = player.translation.truncate();
+
+    if camera_position.distance(player_position) > settings.snap_distance {
+        follow.velocity = Vec2::ZERO;
+        follow.position = player_position;
+        camera_transform.translation = player_position.extend(camera_transform.translation.z);
         return;
+    }
+
+    // Look ahead in the direction the player is moving
+    let look_ahead: Vec2 = (velocity.value.truncate() * settings.look_ahead_time)
+        .clamp_length_max(settings.max_look_ahead);
+    let desired: Vec2 = player_position + look_ahead;
+
+    // Only follow far enough to bring the target back to the edge of the dead zone
+    let offset: Vec2 = desired - camera_position;
+    let distance: f32 = offset.length();
+    let target: Vec2 = if distance > settings.dead_zone {
+        desired - offset / distance * settings.dead_zone
+    } else {
+        camera_position
     };
 
-    let Ok(mut camera_transform) = camera.get_single_mut() else {
+    let position: Vec2 = smooth_damp(
+        camera_position,
+        target,
+        &mut follow.velocity,
+        settings.smooth_time,
+        time.delta_seconds(),
+    );
+
+    follow.position = position;
+    camera_transform.translation = position.extend(camera_transform.translation.z);
+}
+
+fn zoom_camera(
+    mut scroll_events: EventReader<MouseWheel>,
+    mut camera: Query<(&mut OrthographicProjection, &mut FollowCamera), With<MainCamera>>,
+    settings: Res<CameraSettings>,
+    time: Res<Time>,
+) {
+    let Ok((mut projection, mut follow)) = camera.get_single_mut() else {
         return;
     };
 
-    let delta: Vec3 = player.translation - camera_transform.translation;
+    let mut lines: f32 = 0.0;
 
-    if delta != Vec3::ZERO {
-        camera_transform.translation += delta;
+    for event in scroll_events.read() {
+        lines += match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / PIXELS_PER_SCROLL_LINE,
+        };
     }
+
+    // Scrolling up zooms in, which is a smaller projection scale
+    if lines != 0.0 {
+        follow.target_zoom = (follow.target_zoom * (1.0 - settings.zoom_step).powf(lines))
+            .clamp(settings.min_zoom, settings.max_zoom);
+    }
+
+    // Ease towards the target zoom, same as a smooth damp without carrying velocity
+    let blend: f32 = 1.0 - (-time.delta_seconds() / settings.zoom_smooth_time).exp();
+    follow.zoom += (follow.target_zoom - follow.zoom) * blend;
+    projection.scale = follow.zoom;
+}
+
+// Critically damped spring towards the target, never overshoots
+fn smooth_damp(
+    current: Vec2,
+    target: Vec2,
+    velocity: &mut Vec2,
+    smooth_time: f32,
+    delta_seconds: f32,
+) -> Vec2 {
+    let omega: f32 = 2.0 / smooth_time.max(0.0001);
+    let x: f32 = omega * delta_seconds;
+    // approximation of exp(-x)
+    let exp: f32 = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+
+    let change: Vec2 = current - target;
+    let temp: Vec2 = (*velocity + omega * change) * delta_seconds;
+    *velocity = (*velocity - omega * temp) * exp;
+
+    return target + (change + temp) * exp;
 }
@@ -0,0 +1,225 @@
+use crate::camera::{FollowCamera, MainCamera};
+use crate::collision::{OnDamageTaken, OnExplosion};
+use crate::projectile::OnWeaponFired;
+use crate::schedule::RenderSet;
+use crate::state::GameState;
+use bevy::prelude::*;
+
+// Frequencies of the waves making up the shake, picked so they don't line up
+const SHAKE_FREQUENCIES: [f32; 3] = [17.0, 23.0, 31.0];
+const FLASH_Z_INDEX: i32 = 100;
+
+#[derive(Resource, Debug)]
+pub struct CameraEffectSettings {
+    // accessibility option, turns off all screen shake
+    pub shake_enabled: bool,
+    // multiplier for all shake, 1.0 is the default strength
+    pub shake_intensity: f32,
+    pub max_shake_offset: f32,
+    // in radians
+    pub max_shake_angle: f32,
+    // trauma lost per second
+    pub trauma_decay: f32,
+    pub weapon_fire_trauma: f32,
+    pub damage_trauma: f32,
+    pub explosion_trauma: f32,
+    // explosions further than this from the camera don't shake it
+    pub explosion_range: f32,
+    // fraction the view zooms in by on a punch
+    pub zoom_punch: f32,
+    pub zoom_punch_decay: f32,
+    pub flash_colour: Color,
+    pub flash_decay: f32,
+}
+
+impl Default for CameraEffectSettings {
+    fn default() -> Self {
+        Self {
+            shake_enabled: true,
+            shake_intensity: 1.0,
+            max_shake_offset: 18.0,
+            max_shake_angle: 0.05,
+            trauma_decay: 1.5,
+            weapon_fire_trauma: 0.02,
+            damage_trauma: 0.6,
+            explosion_trauma: 0.4,
+            explosion_range: 800.0,
+            zoom_punch: 0.08,
+            zoom_punch_decay: 4.0,
+            flash_colour: Color::rgb(1.0, 0.3, 0.2),
+            flash_decay: 3.0,
+        }
+    }
+}
+
+// Current strength of each effect, all between 0.0 and 1.0
+#[derive(Resource, Debug, Default)]
+pub struct CameraEffects {
+    pub trauma: f32,
+    pub zoom_punch: f32,
+    pub flash: f32,
+    elapsed: f32,
+}
+
+impl CameraEffects {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn punch_zoom(&mut self, amount: f32) {
+        self.zoom_punch = self.zoom_punch.max(amount.clamp(0.0, 1.0));
+    }
+
+    pub fn flash(&mut self, amount: f32) {
+        self.flash = self.flash.max(amount.clamp(0.0, 1.0));
+    }
+}
+
+#[derive(Component, Debug)]
+struct ScreenFlash;
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraEffectSettings>()
+            .init_resource::<CameraEffects>()
+            .add_systems(Startup, spawn_screen_flash)
+            .add_systems(OnEnter(GameState::LoadingGame), reset_camera_effects)
+            .add_systems(
+                Update,
+                (
+                    on_weapon_fired,
+                    on_damage_taken,
+                    on_explosion,
+                    apply_camera_effects,
+                )
+                    .chain()
+                    .in_set(RenderSet::CameraEffects),
+            );
+    }
+}
+
+fn spawn_screen_flash(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            z_index: ZIndex::Global(FLASH_Z_INDEX),
+            ..default()
+        },
+        ScreenFlash,
+    ));
+}
+
+fn reset_camera_effects(mut effects: ResMut<CameraEffects>) {
+    *effects = CameraEffects::default();
+}
+
+fn on_weapon_fired(
+    mut event_reader: EventReader<OnWeaponFired>,
+    mut effects: ResMut<CameraEffects>,
+    settings: Res<CameraEffectSettings>,
+) {
+    for _ in event_reader.read() {
+        effects.add_trauma(settings.weapon_fire_trauma);
+    }
+}
+
+fn on_damage_taken(
+    mut event_reader: EventReader<OnDamageTaken>,
+    mut effects: ResMut<CameraEffects>,
+    settings: Res<CameraEffectSettings>,
+) {
+    for event in event_reader.read() {
+        info!("Player took {} damage", event.amount);
+
+        effects.add_trauma(settings.damage_trauma);
+        effects.flash(1.0);
+    }
+}
+
+fn on_explosion(
+    mut event_reader: EventReader<OnExplosion>,
+    mut effects: ResMut<CameraEffects>,
+    settings: Res<CameraEffectSettings>,
+    camera_query: Query<&FollowCamera, With<MainCamera>>,
+) {
+    let Ok(follow) = camera_query.get_single() else {
+        return;
+    };
+
+    for event in event_reader.read() {
+        // Closer explosions shake harder
+        let distance: f32 = follow.position.distance(event.position.truncate());
+        let falloff: f32 = (1.0 - distance / settings.explosion_range).max(0.0);
+
+        if falloff == 0.0 {
+            continue;
+        }
+
+        effects.add_trauma(settings.explosion_trauma * event.size * falloff);
+        effects.punch_zoom(falloff);
+    }
+}
+
+fn apply_camera_effects(
+    mut effects: ResMut<CameraEffects>,
+    settings: Res<CameraEffectSettings>,
+    mut camera_query: Query<
+        (&FollowCamera, &mut Transform, &mut OrthographicProjection),
+        With<MainCamera>,
+    >,
+    mut flash_query: Query<&mut BackgroundColor, With<ScreenFlash>>,
+    time: Res<Time>,
+) {
+    let delta_seconds: f32 = time.delta_seconds();
+
+    effects.elapsed += delta_seconds;
+    effects.trauma = (effects.trauma - settings.trauma_decay * delta_seconds).max(0.0);
+    effects.zoom_punch = (effects.zoom_punch - settings.zoom_punch_decay * delta_seconds).max(0.0);
+    effects.flash = (effects.flash - settings.flash_decay * delta_seconds).max(0.0);
+
+    let Ok((follow, mut transform, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    // Squaring trauma makes small amounts subtle and big hits obvious
+    let shake: f32 = if settings.shake_enabled {
+        effects.trauma * effects.trauma * settings.shake_intensity
+    } else {
+        0.0
+    };
+
+    let offset: Vec2 = Vec2::new(wave(effects.elapsed, 0.0), wave(effects.elapsed, 1.0))
+        * settings.max_shake_offset
+        * shake;
+    let angle: f32 = wave(effects.elapsed, 2.0) * settings.max_shake_angle * shake;
+
+    transform.translation = (follow.position + offset).extend(transform.translation.z);
+    transform.rotation = Quat::from_rotation_z(angle);
+    projection.scale = follow.zoom * (1.0 - settings.zoom_punch * effects.zoom_punch);
+
+    if let Ok(mut flash_colour) = flash_query.get_single_mut() {
+        *flash_colour = settings
+            .flash_colour
+            .with_a(settings.flash_colour.a() * effects.flash * 0.5)
+            .into();
+    }
+}
+
+// Smooth pseudo random value between -1.0 and 1.0, each seed gives a different wave
+fn wave(time: f32, seed: f32) -> f32 {
+    let mut value: f32 = 0.0;
+
+    for frequency in SHAKE_FREQUENCIES {
+        value += (time * frequency + seed * frequency).sin();
+    }
+
+    return value / SHAKE_FREQUENCIES.len() as f32;
+}
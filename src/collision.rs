@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+// Sent by whatever blows things up or hurts the player, camera effects react to these
+#[derive(Event, Debug)]
+pub struct OnExplosion {
+    pub position: Vec3,
+    // 1.0 for an enemy ship, bigger for bigger explosions
+    pub size: f32,
+}
+
+#[derive(Event, Debug)]
+pub struct OnDamageTaken {
+    pub amount: f32,
+}
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnExplosion>().add_event::<OnDamageTaken>();
+    }
+}
@@ -0,0 +1,101 @@
+use crate::camera::MainCamera;
+use crate::level_manager::Clickable;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy::window::PrimaryWindow;
+use bevy_mod_picking::prelude::*;
+
+const HOVER_TINT: Color = Color::rgb(0.6, 1.0, 0.6);
+
+// World position under the mouse, `None` when the cursor is outside the window
+#[derive(Resource, Debug, Default)]
+pub struct CursorWorldPosition {
+    pub position: Option<Vec2>,
+}
+
+// Entities the picking backend reports as under the cursor
+#[derive(Resource, Debug, Default)]
+pub struct HoveredEntities {
+    pub entities: HashSet<Entity>,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub struct CursorSet;
+
+pub struct CursorPlugin;
+
+impl Plugin for CursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorWorldPosition>()
+            .init_resource::<HoveredEntities>()
+            .add_systems(
+                PreUpdate,
+                update_cursor_world_position
+                    .in_set(CursorSet)
+                    .after(InputSystem),
+            )
+            .add_systems(Update, (update_hovered_entities, highlight_hovered).chain());
+    }
+}
+
+fn update_cursor_world_position(
+    mut cursor: ResMut<CursorWorldPosition>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    cursor.position = None;
+
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    cursor.position = window
+        .cursor_position()
+        .and_then(|position| camera.viewport_to_world_2d(camera_transform, position));
+}
+
+fn update_hovered_entities(
+    mut hovered: ResMut<HoveredEntities>,
+    mut over_reader: EventReader<Pointer<Over>>,
+    mut out_reader: EventReader<Pointer<Out>>,
+    entities: &Entities,
+) {
+    // Despawned entities never get a `Pointer<Out>`, so drop them here
+    if hovered
+        .entities
+        .iter()
+        .any(|entity| !entities.contains(*entity))
+    {
+        hovered.entities.retain(|entity| entities.contains(*entity));
+    }
+
+    for event in out_reader.read() {
+        hovered.entities.remove(&event.target);
+    }
+
+    for event in over_reader.read() {
+        hovered.entities.insert(event.target);
+    }
+}
+
+fn highlight_hovered(
+    hovered: Res<HoveredEntities>,
+    mut query: Query<(Entity, &mut Sprite), With<Clickable>>,
+) {
+    if !hovered.is_changed() {
+        return;
+    }
+
+    for (entity, mut sprite) in query.iter_mut() {
+        sprite.color = if hovered.entities.contains(&entity) {
+            HOVER_TINT
+        } else {
+            Color::WHITE
+        };
+    }
+}
@@ -1,4 +1,3 @@
-use crate::schedule::InGameSet;
 use crate::state::GameState;
 use bevy::prelude::*;
 
@@ -13,7 +12,7 @@ impl Plugin for DebugPlugin {
             2.0,
             TimerMode::Repeating,
         )))
-        .add_systems(Update, log_entity_count.after(InGameSet::EntityUpdates))
+        .add_systems(Update, log_entity_count)
         .add_systems(OnEnter(GameState::StartMenu), log_on_enter_game_state)
         .add_systems(OnEnter(GameState::LoadingGame), log_on_enter_game_state)
         .add_systems(OnEnter(GameState::InGame), log_on_enter_game_state)
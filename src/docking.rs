@@ -0,0 +1,224 @@
+use crate::level_manager::{OnStationClicked, Station};
+use crate::movement::{
+    Acceleration, AngularAcceleration, AngularVelocity, IgnoresGravity, Velocity,
+};
+use crate::player::{player_movement, Player, PlayerInput};
+use crate::schedule::InGameSet;
+use crate::state::GameState;
+use bevy::prelude::*;
+
+const UNDOCK_SPEED: f32 = 120.0;
+const DOCKING_RADIUS_COLOUR: Color = Color::rgba(0.4, 0.9, 0.5, 0.5);
+// Only draw docking radius when the player is this many radii from the station
+const DOCKING_RADIUS_DRAW_DISTANCE: f32 = 3.0;
+
+// Ships within the radius and below the max speed can dock with this station
+#[derive(Component, Debug)]
+pub struct DockingPort {
+    pub radius: f32,
+    pub max_speed: f32,
+}
+
+impl DockingPort {
+    pub fn new(radius: f32, max_speed: f32) -> Self {
+        Self { radius, max_speed }
+    }
+}
+
+// Added to a ship while it is locked to a station
+#[derive(Component, Debug)]
+pub struct Docked {
+    pub station: Entity,
+}
+
+#[derive(Event, Debug)]
+pub struct OnDocked {
+    pub station: Entity,
+}
+
+#[derive(Event, Debug)]
+pub struct OnStationMenuOpen {
+    pub entity: Entity,
+}
+
+pub struct DockingPlugin;
+
+impl Plugin for DockingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OnDocked>()
+            .add_event::<OnStationMenuOpen>()
+            .add_systems(
+                FixedUpdate,
+                (player_docking, hold_docked_ships)
+                    .chain()
+                    .in_set(InGameSet::UserInput)
+                    .after(player_movement),
+            )
+            .add_systems(
+                Update,
+                (
+                    open_station_menu_on_dock.run_if(on_event::<OnDocked>()),
+                    open_station_menu_on_click.run_if(on_event::<OnStationClicked>()),
+                ),
+            )
+            .add_systems(
+                Update,
+                draw_docking_radius.run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn player_docking(
+    mut commands: Commands,
+    mut player_input: ResMut<PlayerInput>,
+    mut player_query: Query<(Entity, &Transform, &mut Velocity, Option<&Docked>), With<Player>>,
+    station_query: Query<(Entity, &Transform, &DockingPort), With<Station>>,
+    mut event_writer: EventWriter<OnDocked>,
+) {
+    if !player_input.dock {
+        return;
+    }
+
+    player_input.dock = false;
+
+    let Ok((entity, transform, mut velocity, docked)) = player_query.get_single_mut() else {
+        return info!("Error trying to get Player for docking");
+    };
+
+    // Undock, pushing the ship away from the station
+    if let Some(docked) = docked {
+        let station_translation: Vec3 = station_query
+            .get(docked.station)
+            .map_or(transform.translation, |(_, station_transform, _)| {
+                station_transform.translation
+            });
+
+        let mut direction: Vec3 = (transform.translation - station_translation)
+            .truncate()
+            .normalize_or_zero()
+            .extend(0.0);
+
+        if direction == Vec3::ZERO {
+            direction = transform.up();
+        }
+
+        velocity.value = direction * UNDOCK_SPEED;
+        commands
+            .entity(entity)
+            .remove::<Docked>()
+            .remove::<IgnoresGravity>();
+
+        return info!("Undocked from {:?}", docked.station);
+    }
+
+    // Dock with the closest station in range
+    let position: Vec2 = transform.translation.truncate();
+    let speed: f32 = velocity.value.length();
+    let mut closest: Option<(Entity, f32)> = None;
+
+    for (station, station_transform, port) in station_query.iter() {
+        let distance: f32 = position.distance(station_transform.translation.truncate());
+
+        if distance > port.radius || speed > port.max_speed {
+            continue;
+        }
+
+        let is_closer: bool = match closest {
+            Some((_, closest_distance)) => distance < closest_distance,
+            None => true,
+        };
+
+        if is_closer {
+            closest = Some((station, distance));
+        }
+    }
+
+    let Some((station, _)) = closest else {
+        return info!("No station in docking range, or moving too fast to dock");
+    };
+
+    velocity.value = Vec3::ZERO;
+    dock_entity(&mut commands, entity, station);
+    event_writer.send(OnDocked { station });
+
+    info!("Docked with {:?}", station);
+}
+
+// Locks a ship to a station, without the `OnDocked` side effects of the player docking
+pub fn dock_entity(commands: &mut Commands, entity: Entity, station: Entity) {
+    commands
+        .entity(entity)
+        .insert((Docked { station }, IgnoresGravity));
+}
+
+fn hold_docked_ships(
+    mut query: Query<
+        (
+            &mut Velocity,
+            &mut Acceleration,
+            &mut AngularVelocity,
+            &mut AngularAcceleration,
+        ),
+        With<Docked>,
+    >,
+) {
+    // Docked ships are locked in place until they undock
+    for (mut velocity, mut acceleration, mut angular_velocity, mut angular_acceleration) in
+        query.iter_mut()
+    {
+        velocity.value = Vec3::ZERO;
+        acceleration.value = Vec3::ZERO;
+        angular_velocity.value = 0.0;
+        angular_acceleration.value = 0.0;
+    }
+}
+
+fn open_station_menu_on_dock(
+    mut event_reader: EventReader<OnDocked>,
+    mut event_writer: EventWriter<OnStationMenuOpen>,
+) {
+    for event in event_reader.read() {
+        event_writer.send(OnStationMenuOpen {
+            entity: event.station,
+        });
+    }
+}
+
+fn open_station_menu_on_click(
+    mut event_reader: EventReader<OnStationClicked>,
+    mut event_writer: EventWriter<OnStationMenuOpen>,
+    query: Query<&Docked, With<Player>>,
+) {
+    for event in event_reader.read() {
+        // Trading is only available at the station the player is docked with
+        let Ok(docked) = query.get_single() else {
+            continue;
+        };
+
+        if event.entity == docked.station {
+            event_writer.send(OnStationMenuOpen {
+                entity: event.entity,
+            });
+        }
+    }
+}
+
+fn draw_docking_radius(
+    mut gizmos: Gizmos,
+    player_query: Query<&Transform, (With<Player>, Without<Docked>)>,
+    station_query: Query<(&Transform, &DockingPort)>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let position: Vec2 = player_transform.translation.truncate();
+
+    for (transform, port) in station_query.iter() {
+        let station_position: Vec2 = transform.translation.truncate();
+
+        if position.distance(station_position) < port.radius * DOCKING_RADIUS_DRAW_DISTANCE {
+            gizmos.circle_2d(station_position, port.radius, DOCKING_RADIUS_COLOUR);
+        }
+    }
+}
@@ -1,9 +1,13 @@
 use crate::asset_loader::ImageAssets;
 use crate::movement::{Acceleration, MovingObjectBundle, Velocity};
-use crate::schedule::InGameSet;
+use crate::schedule::{InGameSet, SimulationRng};
+use crate::settings::GameSettings;
+use crate::state::{GameState, SessionScoped, SessionSetupSet};
+use crate::world_bounds::{Bounded, BoundsClass};
 use bevy::prelude::*;
 use rand::prelude::*;
 use std::ops::Range;
+use std::time::Duration;
 
 const VELOCITY_SCALAR: f32 = 50.0;
 const ACCELERATION_SCALAR: f32 = 1.0;
@@ -22,6 +26,14 @@ pub struct SpawnTimer {
     timer: Timer,
 }
 
+impl SpawnTimer {
+    // Scales the time between spawns, lower values spawn enemies more often
+    pub fn set_interval_scale(&mut self, scale: f32) {
+        self.timer
+            .set_duration(Duration::from_secs_f32(SPAWN_TIME_SECONDS * scale));
+    }
+}
+
 pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
@@ -29,23 +41,37 @@ impl Plugin for EnemyPlugin {
         app.insert_resource(SpawnTimer {
             timer: Timer::from_seconds(SPAWN_TIME_SECONDS, TimerMode::Repeating),
         })
-        .add_systems(Update, spawn_enemy.in_set(InGameSet::UserInput))
-        .add_systems(Update, despawn_enemy.in_set(InGameSet::DespawnEntities));
+        .add_systems(
+            OnEnter(GameState::LoadingGame),
+            reset_spawn_timer.in_set(SessionSetupSet),
+        )
+        .add_systems(FixedUpdate, spawn_enemy.in_set(InGameSet::UserInput))
+        .add_systems(
+            FixedUpdate,
+            despawn_enemy.in_set(InGameSet::DespawnEntities),
+        );
     }
 }
 
+// Difficulty is fixed for the whole session, so a change only applies from the next one
+fn reset_spawn_timer(mut spawn_timer: ResMut<SpawnTimer>, settings: Res<GameSettings>) {
+    spawn_timer.set_interval_scale(settings.gameplay.difficulty.spawn_interval_scale());
+    spawn_timer.timer.reset();
+}
+
 fn spawn_enemy(
     mut commands: Commands,
     mut spawn_timer: ResMut<SpawnTimer>,
     time: Res<Time>,
     image_assets: Res<ImageAssets>,
+    mut simulation_rng: ResMut<SimulationRng>,
 ) {
     spawn_timer.timer.tick(time.delta());
     if !spawn_timer.timer.just_finished() {
         return;
     }
 
-    let mut rng = rand::thread_rng();
+    let rng: &mut StdRng = &mut simulation_rng.0;
 
     let translation = Vec3::new(
         rng.gen_range(SPAWN_RANGE_X),
@@ -53,9 +79,27 @@ fn spawn_enemy(
         2.0,
     );
 
-    let velocity = random_unit_vector(&mut rng) * VELOCITY_SCALAR;
-    let acceleration = random_unit_vector(&mut rng) * ACCELERATION_SCALAR;
+    let velocity = random_unit_vector(rng) * VELOCITY_SCALAR;
+    let acceleration = random_unit_vector(rng) * ACCELERATION_SCALAR;
 
+    spawn_enemy_entity(
+        &mut commands,
+        &image_assets,
+        translation,
+        velocity,
+        acceleration,
+        Timer::from_seconds(DESPAWN_TIME_SECONDS, TimerMode::Once),
+    );
+}
+
+pub fn spawn_enemy_entity(
+    commands: &mut Commands,
+    image_assets: &ImageAssets,
+    translation: Vec3,
+    velocity: Vec3,
+    acceleration: Vec3,
+    despawn_timer: Timer,
+) {
     commands.spawn((
         MovingObjectBundle {
             acceleration: Acceleration::new(acceleration),
@@ -66,9 +110,9 @@ fn spawn_enemy(
                 ..default()
             },
         },
-        Enemy {
-            despawn_timer: Timer::from_seconds(DESPAWN_TIME_SECONDS, TimerMode::Once),
-        },
+        Enemy { despawn_timer },
+        Bounded::new(BoundsClass::Enemy),
+        SessionScoped,
     ));
 }
 
@@ -86,6 +130,6 @@ fn despawn_enemy(
     }
 }
 
-fn random_unit_vector(rng: &mut ThreadRng) -> Vec3 {
+fn random_unit_vector(rng: &mut impl Rng) -> Vec3 {
     return Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0), 0.0).normalize_or_zero();
 }
@@ -0,0 +1,381 @@
+use crate::settings::{ControlScheme, GameSettings};
+use bevy::input::mouse::MouseMotion;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+// Gamepads use a fixed layout, with the left stick flying the ship
+const GAMEPAD_BUTTONS: [(GamepadButtonType, InputAction); 6] = [
+    (GamepadButtonType::RightTrigger2, InputAction::Fire),
+    (GamepadButtonType::LeftTrigger2, InputAction::Fire),
+    (GamepadButtonType::South, InputAction::Dock),
+    (GamepadButtonType::West, InputAction::ToggleDampener),
+    (GamepadButtonType::North, InputAction::RadarMode),
+    (GamepadButtonType::Start, InputAction::Pause),
+];
+
+// Something the player can do, bound to a key or mouse button in the settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputAction {
+    Thrust,
+    Reverse,
+    TurnLeft,
+    TurnRight,
+    Fire,
+    ToggleDampener,
+    Dock,
+    SetWaypoint,
+    QueueWaypoint,
+    RadarMode,
+    Pause,
+}
+
+impl InputAction {
+    // In the order they're listed on the settings screen
+    pub const ALL: [InputAction; 11] = [
+        InputAction::Thrust,
+        InputAction::Reverse,
+        InputAction::TurnLeft,
+        InputAction::TurnRight,
+        InputAction::Fire,
+        InputAction::ToggleDampener,
+        InputAction::Dock,
+        InputAction::SetWaypoint,
+        InputAction::QueueWaypoint,
+        InputAction::RadarMode,
+        InputAction::Pause,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputAction::Thrust => "Thrust",
+            InputAction::Reverse => "Reverse",
+            InputAction::TurnLeft => "Turn / Strafe Left",
+            InputAction::TurnRight => "Turn / Strafe Right",
+            InputAction::Fire => "Fire",
+            InputAction::ToggleDampener => "Inertial Dampener",
+            InputAction::Dock => "Dock",
+            InputAction::SetWaypoint => "Set Waypoint",
+            InputAction::QueueWaypoint => "Queue Waypoint (hold)",
+            InputAction::RadarMode => "Radar Mode",
+            InputAction::Pause => "Pause",
+        }
+    }
+
+    fn default_binding(&self) -> InputBinding {
+        match self {
+            InputAction::Thrust => InputBinding::Key(KeyCode::W),
+            InputAction::Reverse => InputBinding::Key(KeyCode::S),
+            InputAction::TurnLeft => InputBinding::Key(KeyCode::A),
+            InputAction::TurnRight => InputBinding::Key(KeyCode::D),
+            InputAction::Fire => InputBinding::Key(KeyCode::Space),
+            InputAction::ToggleDampener => InputBinding::Key(KeyCode::X),
+            InputAction::Dock => InputBinding::Key(KeyCode::F),
+            InputAction::SetWaypoint => InputBinding::Mouse(MouseButton::Right),
+            InputAction::QueueWaypoint => InputBinding::Key(KeyCode::ShiftLeft),
+            InputAction::RadarMode => InputBinding::Key(KeyCode::N),
+            InputAction::Pause => InputBinding::Key(KeyCode::Escape),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl InputBinding {
+    pub fn label(&self) -> String {
+        return match self {
+            InputBinding::Key(key) => format!("{:?}", key),
+            InputBinding::Mouse(MouseButton::Left) => "Left Click".to_string(),
+            InputBinding::Mouse(MouseButton::Right) => "Right Click".to_string(),
+            InputBinding::Mouse(MouseButton::Middle) => "Middle Click".to_string(),
+            InputBinding::Mouse(MouseButton::Other(button)) => format!("Mouse {}", button),
+        };
+    }
+}
+
+// One binding per action, saved with the rest of the settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    bindings: HashMap<InputAction, InputBinding>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let bindings: HashMap<InputAction, InputBinding> = InputAction::ALL
+            .iter()
+            .map(|action| (*action, action.default_binding()))
+            .collect();
+
+        Self { bindings }
+    }
+}
+
+impl InputBindings {
+    pub fn binding(&self, action: InputAction) -> InputBinding {
+        return self
+            .bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding());
+    }
+
+    pub fn bind(&mut self, action: InputAction, binding: InputBinding) {
+        self.bindings.insert(action, binding);
+    }
+
+    // Everything that triggers the action, its own binding plus any the control scheme adds
+    pub fn triggers(&self, action: InputAction, scheme: ControlScheme) -> Vec<InputBinding> {
+        let mut triggers: Vec<InputBinding> = vec![self.binding(action)];
+
+        // Mouse aim always fires with the left button, on top of the Fire binding
+        if action == InputAction::Fire && scheme == ControlScheme::MouseAim {
+            triggers.push(InputBinding::Mouse(MouseButton::Left));
+        }
+
+        return triggers;
+    }
+
+    // Other actions sharing something that triggers this action
+    pub fn conflicts(&self, action: InputAction, scheme: ControlScheme) -> Vec<InputAction> {
+        let triggers: Vec<InputBinding> = self.triggers(action, scheme);
+
+        return InputAction::ALL
+            .into_iter()
+            .filter(|other| {
+                *other != action
+                    && self
+                        .triggers(*other, scheme)
+                        .iter()
+                        .any(|binding| triggers.contains(binding))
+            })
+            .collect();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputDevice {
+    #[default]
+    KeyboardMouse,
+    Gamepad(Gamepad),
+}
+
+// The device the player last used, only its input is read
+#[derive(Resource, Debug, Default)]
+pub struct ActiveInputDevice {
+    pub device: InputDevice,
+}
+
+// Actions held or pressed this frame, read by gameplay systems instead of raw input
+#[derive(Resource, Debug, Default)]
+pub struct ActionState {
+    // how far each held action is pressed, 1.0 for buttons and up to 1.0 for sticks
+    values: HashMap<InputAction, f32>,
+    just_pressed: HashSet<InputAction>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        return self.values.contains_key(&action);
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        return self.just_pressed.contains(&action);
+    }
+
+    pub fn value(&self, action: InputAction) -> f32 {
+        return self.values.get(&action).copied().unwrap_or(0.0);
+    }
+
+    // Positive when the positive action is held harder than the negative one, from -1.0 to 1.0
+    pub fn axis(&self, positive: InputAction, negative: InputAction) -> f32 {
+        return self.value(positive) - self.value(negative);
+    }
+
+    fn press(&mut self, action: InputAction, value: f32) {
+        let current: &mut f32 = self.values.entry(action).or_insert(0.0);
+        *current = current.max(value.min(1.0));
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+        self.just_pressed.clear();
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub struct InputActionSet;
+
+pub struct InputActionPlugin;
+
+impl Plugin for InputActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>()
+            .init_resource::<ActiveInputDevice>()
+            .add_systems(
+                PreUpdate,
+                (detect_active_device, update_action_state)
+                    .chain()
+                    .in_set(InputActionSet)
+                    .after(InputSystem),
+            );
+    }
+}
+
+// Left stick position with the deadzone removed, rescaled so full tilt is still 1.0
+fn left_stick(axes: &Axis<GamepadAxis>, gamepad: Gamepad, deadzone: f32) -> Vec2 {
+    let stick: Vec2 = Vec2::new(
+        axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+            .unwrap_or(0.0),
+        axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+            .unwrap_or(0.0),
+    );
+    let length: f32 = stick.length();
+
+    if length <= deadzone {
+        return Vec2::ZERO;
+    }
+
+    return stick / length * ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+}
+
+fn detect_active_device(
+    settings: Res<GameSettings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut active_device: ResMut<ActiveInputDevice>,
+) {
+    let mouse_moved: bool = mouse_motion.read().count() > 0;
+
+    let mut device: Option<InputDevice> = None;
+
+    if keyboard_input.get_just_pressed().next().is_some()
+        || mouse_input.get_just_pressed().next().is_some()
+        || mouse_moved
+    {
+        device = Some(InputDevice::KeyboardMouse);
+    }
+
+    for gamepad in gamepads.iter() {
+        let button_pressed: bool = gamepad_buttons
+            .get_just_pressed()
+            .any(|button| button.gamepad == gamepad);
+        let stick_moved: bool =
+            left_stick(&gamepad_axes, gamepad, settings.gameplay.stick_deadzone) != Vec2::ZERO;
+
+        if button_pressed || stick_moved {
+            device = Some(InputDevice::Gamepad(gamepad));
+        }
+    }
+
+    // Only write on a switch so change detection can be used to react to it
+    if let Some(device) = device {
+        if active_device.device != device {
+            active_device.device = device;
+            info!("Switched input device to {:?}", device);
+        }
+    }
+}
+
+fn update_action_state(
+    settings: Res<GameSettings>,
+    active_device: Res<ActiveInputDevice>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut action_state: ResMut<ActionState>,
+) {
+    action_state.clear();
+
+    match active_device.device {
+        InputDevice::KeyboardMouse => {
+            read_keyboard_mouse(&settings, &keyboard_input, &mouse_input, &mut action_state)
+        }
+        InputDevice::Gamepad(gamepad) => read_gamepad(
+            &settings,
+            gamepad,
+            &gamepad_buttons,
+            &gamepad_axes,
+            &mut action_state,
+        ),
+    }
+}
+
+fn read_keyboard_mouse(
+    settings: &GameSettings,
+    keyboard_input: &Input<KeyCode>,
+    mouse_input: &Input<MouseButton>,
+    action_state: &mut ActionState,
+) {
+    for action in InputAction::ALL {
+        for binding in settings
+            .controls
+            .triggers(action, settings.gameplay.control_scheme)
+        {
+            let (pressed, just_pressed) = match binding {
+                InputBinding::Key(key) => (
+                    keyboard_input.pressed(key),
+                    keyboard_input.just_pressed(key),
+                ),
+                InputBinding::Mouse(button) => (
+                    mouse_input.pressed(button),
+                    mouse_input.just_pressed(button),
+                ),
+            };
+
+            if pressed {
+                action_state.press(action, 1.0);
+            }
+
+            if just_pressed {
+                action_state.just_pressed.insert(action);
+            }
+        }
+    }
+}
+
+fn read_gamepad(
+    settings: &GameSettings,
+    gamepad: Gamepad,
+    gamepad_buttons: &Input<GamepadButton>,
+    gamepad_axes: &Axis<GamepadAxis>,
+    action_state: &mut ActionState,
+) {
+    for (button_type, action) in GAMEPAD_BUTTONS {
+        let button: GamepadButton = GamepadButton::new(gamepad, button_type);
+
+        if gamepad_buttons.pressed(button) {
+            action_state.press(action, 1.0);
+        }
+
+        if gamepad_buttons.just_pressed(button) {
+            action_state.just_pressed.insert(action);
+        }
+    }
+
+    let stick: Vec2 = left_stick(gamepad_axes, gamepad, settings.gameplay.stick_deadzone);
+
+    // Each half of an axis drives its own action, so the stick and buttons share one code path
+    let stick_actions: [(f32, InputAction); 4] = [
+        (stick.y, InputAction::Thrust),
+        (-stick.y, InputAction::Reverse),
+        (-stick.x, InputAction::TurnLeft),
+        (stick.x, InputAction::TurnRight),
+    ];
+
+    for (value, action) in stick_actions {
+        if value > 0.0 {
+            action_state.press(action, value);
+        }
+    }
+}
@@ -1,14 +1,15 @@
 use crate::state::GameState;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ItemType {
     EnergyCell,
     IronOre,
     Silica,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub item_type: ItemType,
     pub name: String,
@@ -16,12 +17,30 @@ pub struct Item {
     pub quantity: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Inventory {
     pub items: Vec<Item>,
     pub size: usize,
 }
 
+// Credits a ship has to trade with
+#[derive(Component, Debug)]
+pub struct Wallet {
+    pub credits: usize,
+}
+
+impl Wallet {
+    pub fn new(credits: usize) -> Self {
+        return Self { credits };
+    }
+}
+
+// Items carried in a ship's hold
+#[derive(Component, Debug, Default)]
+pub struct Cargo {
+    pub inventory: Inventory,
+}
+
 impl Item {
     pub fn new(item_type: ItemType, value: usize, quantity: usize) -> Self {
         let name: String = match item_type {
@@ -1,6 +1,8 @@
 use crate::asset_loader::ImageAssets;
+use crate::docking::DockingPort;
 use crate::item_manager::*;
-use crate::state::GameState;
+use crate::movement::GravitySource;
+use crate::state::{GameState, SessionScoped, SessionSetupSet};
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
@@ -32,12 +34,26 @@ impl From<ListenerInput<Pointer<Click>>> for OnStationClicked {
 #[derive(Component, Debug)]
 pub struct Clickable;
 
+#[derive(Component, Debug)]
+pub enum CelestialBody {
+    Star,
+    Planet,
+}
+
+const DOCKING_RADIUS: f32 = 90.0;
+const DOCKING_MAX_SPEED: f32 = 60.0;
+const STAR_COLOUR: Color = Color::rgb(1.0, 0.85, 0.4);
+const PLANET_COLOUR: Color = Color::rgb(0.3, 0.55, 0.9);
+
 pub struct LevelManagerPlugin;
 
 impl Plugin for LevelManagerPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<OnStationClicked>()
-            .add_systems(OnEnter(GameState::InGame), setup)
+            .add_systems(
+                OnEnter(GameState::LoadingGame),
+                setup.in_set(SessionSetupSet),
+            )
             .add_systems(
                 Update,
                 on_station_clicked.run_if(on_event::<OnStationClicked>()),
@@ -62,8 +78,10 @@ fn setup(mut commands: Commands, image_assets: Res<ImageAssets>) {
             ..default()
         },
         station,
+        DockingPort::new(DOCKING_RADIUS, DOCKING_MAX_SPEED),
         Clickable,
         On::<Pointer<Click>>::send_event::<OnStationClicked>(),
+        SessionScoped,
     ));
 
     // Spawn station number 2
@@ -80,8 +98,42 @@ fn setup(mut commands: Commands, image_assets: Res<ImageAssets>) {
             ..default()
         },
         station,
+        DockingPort::new(DOCKING_RADIUS, DOCKING_MAX_SPEED),
         Clickable,
         On::<Pointer<Click>>::send_event::<OnStationClicked>(),
+        SessionScoped,
+    ));
+
+    // Spawn a star, its gravity reaches most of the level
+    commands.spawn((
+        SpriteBundle {
+            texture: image_assets.projectile.clone(),
+            sprite: Sprite {
+                color: STAR_COLOUR,
+                ..default()
+            },
+            transform: Transform::from_xyz(-700.0, 300.0, 0.5).with_scale(Vec3::splat(2.0)),
+            ..default()
+        },
+        CelestialBody::Star,
+        GravitySource::new(4000.0, 900.0, 128.0),
+        SessionScoped,
+    ));
+
+    // Spawn a planet
+    commands.spawn((
+        SpriteBundle {
+            texture: image_assets.projectile.clone(),
+            sprite: Sprite {
+                color: PLANET_COLOUR,
+                ..default()
+            },
+            transform: Transform::from_xyz(600.0, -400.0, 0.5),
+            ..default()
+        },
+        CelestialBody::Planet,
+        GravitySource::new(1000.0, 400.0, 64.0),
+        SessionScoped,
     ));
 }
 
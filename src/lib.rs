@@ -0,0 +1,26 @@
+pub mod asset_loader;
+pub mod autopilot;
+pub mod camera;
+pub mod camera_effects;
+pub mod collision;
+pub mod cursor;
+pub mod debug;
+pub mod docking;
+pub mod enemy;
+pub mod input;
+pub mod item_manager;
+pub mod level_manager;
+pub mod movement;
+pub mod player;
+pub mod projectile;
+pub mod replay;
+pub mod save_game;
+pub mod schedule;
+pub mod settings;
+pub mod starfield;
+pub mod state;
+#[cfg(test)]
+mod testing;
+pub mod ui;
+pub mod util;
+pub mod world_bounds;
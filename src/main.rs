@@ -1,29 +1,28 @@
-mod asset_loader;
-mod camera;
-mod debug;
-mod enemy;
-mod item_manager;
-mod level_manager;
-mod movement;
-mod player;
-mod schedule;
-mod state;
-mod ui;
-mod util;
-
-use asset_loader::AssetLoaderPlugin;
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
-use camera::CameraPlugin;
-// use debug::DebugPlugin;
-use enemy::EnemyPlugin;
-use item_manager::ItemManagerPlugin;
-use level_manager::LevelManagerPlugin;
-use movement::MovementPlugin;
-use player::PlayerPlugin;
-use schedule::SchedulePlugin;
-use state::StatePlugin;
-use ui::UIPlugin;
+use bevy_prototyping::asset_loader::AssetLoaderPlugin;
+use bevy_prototyping::autopilot::AutopilotPlugin;
+use bevy_prototyping::camera::CameraPlugin;
+use bevy_prototyping::camera_effects::CameraEffectsPlugin;
+use bevy_prototyping::collision::CollisionPlugin;
+use bevy_prototyping::cursor::CursorPlugin;
+// use bevy_prototyping::debug::DebugPlugin;
+use bevy_prototyping::docking::DockingPlugin;
+use bevy_prototyping::enemy::EnemyPlugin;
+use bevy_prototyping::input::InputActionPlugin;
+use bevy_prototyping::item_manager::ItemManagerPlugin;
+use bevy_prototyping::level_manager::LevelManagerPlugin;
+use bevy_prototyping::movement::MovementPlugin;
+use bevy_prototyping::player::PlayerPlugin;
+use bevy_prototyping::projectile::ProjectilePlugin;
+use bevy_prototyping::replay::ReplayPlugin;
+use bevy_prototyping::save_game::SaveGamePlugin;
+use bevy_prototyping::schedule::SchedulePlugin;
+use bevy_prototyping::settings::SettingsPlugin;
+use bevy_prototyping::starfield::StarfieldPlugin;
+use bevy_prototyping::state::StatePlugin;
+use bevy_prototyping::ui::UIPlugin;
+use bevy_prototyping::world_bounds::WorldBoundsPlugin;
 
 #[derive(Component, Debug)]
 struct Name(String);
@@ -33,15 +32,27 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_plugins(DefaultPickingPlugins)
         .add_plugins(CameraPlugin)
+        .add_plugins(CameraEffectsPlugin)
+        .add_plugins(CursorPlugin)
+        .add_plugins(InputActionPlugin)
+        .add_plugins(StarfieldPlugin)
         .add_plugins(AssetLoaderPlugin)
         .add_plugins(UIPlugin)
         .add_plugins(PlayerPlugin)
+        .add_plugins(ProjectilePlugin)
         .add_plugins(MovementPlugin)
+        .add_plugins(WorldBoundsPlugin)
+        .add_plugins(CollisionPlugin)
         .add_plugins(EnemyPlugin)
         .add_plugins(SchedulePlugin)
         // .add_plugins(DebugPlugin)
         .add_plugins(StatePlugin)
+        .add_plugins(SettingsPlugin)
+        .add_plugins(SaveGamePlugin)
+        .add_plugins(ReplayPlugin)
         .add_plugins(LevelManagerPlugin)
+        .add_plugins(DockingPlugin)
+        .add_plugins(AutopilotPlugin)
         .add_plugins(ItemManagerPlugin)
         .run();
 }
@@ -1,6 +1,10 @@
-use crate::schedule::InGameSet;
+use crate::schedule::{InGameSet, RenderSet};
+use crate::state::GameState;
+use crate::world_bounds::{Bounded, OnLeftWorldBounds, WorldBounds};
 use bevy::prelude::*;
 
+const GRAVITATIONAL_CONSTANT: f32 = 1000.0;
+
 #[derive(Component, Debug)]
 pub struct Velocity {
     pub value: Vec3,
@@ -14,28 +18,192 @@ impl Velocity {
 
 #[derive(Component, Debug)]
 pub struct Acceleration {
+    // acceleration the entity applies itself, i.e. thrust
     pub value: Vec3,
+    // acceleration from the world, i.e. gravity, recalculated every tick
+    pub external: Vec3,
 }
 
 impl Acceleration {
     pub fn new(value: Vec3) -> Self {
+        Self {
+            value,
+            external: Vec3::ZERO,
+        }
+    }
+}
+
+// Not pulled by gravity sources, for entities their owning plugin has parked in place
+#[derive(Component, Debug)]
+pub struct IgnoresGravity;
+
+// Linear drag, removes this fraction of velocity per second
+#[derive(Component, Debug)]
+pub struct Drag {
+    pub value: f32,
+}
+
+impl Drag {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct MaxSpeed {
+    pub value: f32,
+}
+
+impl MaxSpeed {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+}
+
+// Extra drag that brings the entity to a stop while it isn't accelerating
+#[derive(Component, Debug)]
+pub struct InertialDampener {
+    pub enabled: bool,
+    pub strength: f32,
+}
+
+impl InertialDampener {
+    pub fn new(strength: f32) -> Self {
+        Self {
+            enabled: true,
+            strength,
+        }
+    }
+}
+
+// Rotation around the Z axis, in radians per second
+#[derive(Component, Debug)]
+pub struct AngularVelocity {
+    pub value: f32,
+}
+
+impl AngularVelocity {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct AngularAcceleration {
+    pub value: f32,
+}
+
+impl AngularAcceleration {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+}
+
+// Removes this fraction of angular velocity per second
+#[derive(Component, Debug)]
+pub struct AngularDamping {
+    pub value: f32,
+}
+
+impl AngularDamping {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct MaxAngularSpeed {
+    pub value: f32,
+}
+
+impl MaxAngularSpeed {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+}
+
+// Angular acceleration a ship's thrusters give at full turn input,
+// divided by the ship's `Mass` so heavier ships turn slower
+#[derive(Component, Debug)]
+pub struct TurnRate {
+    pub value: f32,
+}
+
+impl TurnRate {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+
+    pub fn angular_acceleration(&self, turn: f32, mass: &Mass) -> f32 {
+        return turn * self.value / mass.value;
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Mass {
+    pub value: f32,
+}
+
+impl Mass {
+    pub fn new(value: f32) -> Self {
         Self { value }
     }
 }
 
+// Pulls every entity with a `Velocity` within its radius of influence towards it
 #[derive(Component, Debug)]
-pub struct Rotation {
-    pub speed: f32,
-    pub factor: f32,
+pub struct GravitySource {
+    pub mass: f32,
+    pub radius_of_influence: f32,
+    // pull is capped at this distance so it doesn't go to infinity at the centre
+    pub surface_radius: f32,
 }
 
-impl Rotation {
-    pub fn new() -> Self {
+impl GravitySource {
+    pub fn new(mass: f32, radius_of_influence: f32, surface_radius: f32) -> Self {
         Self {
-            speed: f32::to_radians(260.0),
-            factor: 0.0,
+            mass,
+            radius_of_influence,
+            surface_radius,
         }
     }
+
+    pub fn acceleration_at(&self, source: Vec3, position: Vec3) -> Vec3 {
+        let offset: Vec3 = (source - position).truncate().extend(0.0);
+        let distance: f32 = offset.length();
+
+        if distance > self.radius_of_influence || distance == 0.0 {
+            return Vec3::ZERO;
+        }
+
+        let distance: f32 = distance.max(self.surface_radius);
+        let strength: f32 = GRAVITATIONAL_CONSTANT * self.mass / (distance * distance);
+
+        return offset.normalize() * strength;
+    }
+}
+
+// Transform of the entity at the previous and current simulation tick,
+// the rendered `Transform` is interpolated between the two
+#[derive(Component, Debug)]
+pub struct TransformInterpolation {
+    pub previous: Transform,
+    pub current: Transform,
+}
+
+impl TransformInterpolation {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            previous: transform,
+            current: transform,
+        }
+    }
+
+    // Moves the entity without interpolating from where it was
+    pub fn teleport(&mut self, transform: Transform) {
+        self.previous = transform;
+        self.current = transform;
+    }
 }
 
 #[derive(Bundle)]
@@ -50,29 +218,200 @@ pub struct MovementPlugin;
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
-            (update_velocity, update_position, update_rotation)
+            FixedUpdate,
+            restore_simulation_transform
+                .before(InGameSet::DespawnEntities)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                apply_gravity,
+                update_velocity,
+                update_angular_velocity,
+                update_position,
+                update_rotation,
+            )
                 .chain()
                 .in_set(InGameSet::EntityUpdates),
+        )
+        .add_systems(
+            FixedUpdate,
+            (record_simulation_transform, add_transform_interpolation)
+                .after(InGameSet::CollisionDetection)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            Update,
+            interpolate_transforms.in_set(RenderSet::Interpolation),
         );
     }
 }
 
-fn update_velocity(mut query: Query<(&Acceleration, &mut Velocity)>, time: Res<Time>) {
-    for (acceleration, mut velocity) in query.iter_mut() {
-        velocity.value += acceleration.value * time.delta_seconds();
+fn apply_gravity(
+    sources: Query<(&GravitySource, &Transform)>,
+    mut query: Query<(&Transform, &mut Acceleration, Has<IgnoresGravity>), With<Velocity>>,
+) {
+    for (transform, mut acceleration, ignores_gravity) in query.iter_mut() {
+        acceleration.external = Vec3::ZERO;
+
+        if ignores_gravity {
+            continue;
+        }
+
+        for (source, source_transform) in sources.iter() {
+            acceleration.external +=
+                source.acceleration_at(source_transform.translation, transform.translation);
+        }
+    }
+}
+
+fn update_velocity(
+    mut query: Query<(
+        &Acceleration,
+        &mut Velocity,
+        Option<&Drag>,
+        Option<&MaxSpeed>,
+        Option<&InertialDampener>,
+    )>,
+    time: Res<Time>,
+) {
+    let delta_seconds: f32 = time.delta_seconds();
+
+    for (acceleration, mut velocity, drag, max_speed, dampener) in query.iter_mut() {
+        velocity.value += (acceleration.value + acceleration.external) * delta_seconds;
+
+        let mut drag_value: f32 = drag.map_or(0.0, |drag| drag.value);
+
+        if let Some(dampener) = dampener {
+            if dampener.enabled && acceleration.value == Vec3::ZERO {
+                drag_value += dampener.strength;
+            }
+        }
+
+        // don't let drag push velocity past zero on long frames
+        let drag_factor: f32 = (1.0 - drag_value * delta_seconds).max(0.0);
+        velocity.value *= drag_factor;
+
+        if let Some(max_speed) = max_speed {
+            velocity.value = velocity.value.clamp_length_max(max_speed.value);
+        }
+    }
+}
+
+fn update_position(
+    mut query: Query<(
+        Entity,
+        &mut Velocity,
+        &mut Transform,
+        Option<&mut Bounded>,
+        Option<&mut TransformInterpolation>,
+    )>,
+    bounds: Res<WorldBounds>,
+    mut event_writer: EventWriter<OnLeftWorldBounds>,
+    time: Res<Time>,
+) {
+    let delta_seconds: f32 = time.delta_seconds();
+
+    for (entity, mut velocity, mut transform, bounded, interpolation) in query.iter_mut() {
+        transform.translation += velocity.value * delta_seconds;
+
+        let Some(mut bounded) = bounded else {
+            continue;
+        };
+
+        let event: Option<OnLeftWorldBounds> = bounds.apply(
+            entity,
+            &mut bounded,
+            &mut transform,
+            &mut velocity,
+            interpolation,
+            delta_seconds,
+        );
+
+        if let Some(event) = event {
+            event_writer.send(event);
+        }
     }
 }
 
-fn update_position(mut query: Query<(&Velocity, &mut Transform)>, time: Res<Time>) {
-    for (velocity, mut transform) in query.iter_mut() {
-        transform.translation += velocity.value * time.delta_seconds();
+fn update_angular_velocity(
+    mut query: Query<(
+        &AngularAcceleration,
+        &mut AngularVelocity,
+        Option<&AngularDamping>,
+        Option<&MaxAngularSpeed>,
+        Option<&InertialDampener>,
+    )>,
+    time: Res<Time>,
+) {
+    let delta_seconds: f32 = time.delta_seconds();
+
+    for (acceleration, mut angular_velocity, damping, max_speed, dampener) in query.iter_mut() {
+        angular_velocity.value += acceleration.value * delta_seconds;
+
+        let mut damping_value: f32 = damping.map_or(0.0, |damping| damping.value);
+
+        if let Some(dampener) = dampener {
+            if dampener.enabled && acceleration.value == 0.0 {
+                damping_value += dampener.strength;
+            }
+        }
+
+        angular_velocity.value *= (1.0 - damping_value * delta_seconds).max(0.0);
+
+        if let Some(max_speed) = max_speed {
+            angular_velocity.value = angular_velocity
+                .value
+                .clamp(-max_speed.value, max_speed.value);
+        }
     }
 }
 
-fn update_rotation(mut query: Query<(&Rotation, &mut Transform)>, time: Res<Time>) {
-    for (rotation, mut transform) in query.iter_mut() {
+fn update_rotation(mut query: Query<(&AngularVelocity, &mut Transform)>, time: Res<Time>) {
+    for (angular_velocity, mut transform) in query.iter_mut() {
         // update the ship rotation around the Z axis (perpendicular to the 2D plane of the screen)
-        transform.rotate_z(rotation.factor * rotation.speed * time.delta_seconds());
+        transform.rotate_z(angular_velocity.value * time.delta_seconds());
+    }
+}
+
+fn restore_simulation_transform(mut query: Query<(&mut Transform, &mut TransformInterpolation)>) {
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        // undo the interpolation applied for rendering so the simulation steps from its own state
+        *transform = interpolation.current;
+        interpolation.previous = interpolation.current;
+    }
+}
+
+fn record_simulation_transform(mut query: Query<(&Transform, &mut TransformInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.current = *transform;
+    }
+}
+
+fn add_transform_interpolation(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), (With<Velocity>, Without<TransformInterpolation>)>,
+) {
+    for (entity, transform) in query.iter() {
+        commands
+            .entity(entity)
+            .insert(TransformInterpolation::new(*transform));
+    }
+}
+
+fn interpolate_transforms(
+    mut query: Query<(&mut Transform, &TransformInterpolation)>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    // how far we are between the last simulation tick and the next one
+    let alpha: f32 = fixed_time.overstep_fraction();
+
+    for (mut transform, interpolation) in query.iter_mut() {
+        let previous: &Transform = &interpolation.previous;
+        let current: &Transform = &interpolation.current;
+
+        transform.translation = previous.translation.lerp(current.translation, alpha);
+        transform.rotation = previous.rotation.slerp(current.rotation, alpha);
     }
 }
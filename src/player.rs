@@ -1,43 +1,99 @@
 use crate::asset_loader::ImageAssets;
-use crate::movement::{Acceleration, MovingObjectBundle, Rotation, Velocity};
+use crate::autopilot::{Autopilot, WaypointOrder};
+use crate::cursor::{CursorSet, CursorWorldPosition};
+use crate::docking::Docked;
+use crate::input::{ActionState, ActiveInputDevice, InputAction, InputActionSet, InputDevice};
+use crate::item_manager::{Cargo, Wallet};
+use crate::movement::{
+    Acceleration, AngularAcceleration, AngularDamping, AngularVelocity, Drag, InertialDampener,
+    Mass, MaxAngularSpeed, MaxSpeed, MovingObjectBundle, TurnRate, Velocity,
+};
+use crate::projectile::{fire_projectile, OnWeaponFired, ProjectilePool, ProjectileQuery, Weapon};
 use crate::schedule::InGameSet;
-use crate::state::GameState;
+use crate::settings::{ControlScheme, GameSettings, ThrustSpace};
+use crate::state::{GameState, SessionScoped, SessionSetupSet};
+use crate::world_bounds::{Bounded, BoundsClass};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub struct PlayerPlugin;
 
 #[derive(Component, Debug)]
 pub struct Player;
 
-#[derive(Component, Debug)]
-pub struct Projectile {
-    pub despawn_timer: Timer,
+// Player input sampled every frame, consumed by the simulation on its next tick
+#[derive(Resource, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInput {
+    // -1.0 for reverse thrust up to 1.0 for full forward thrust
+    pub thrust: f32,
+    // positive turns left (counter clockwise)
+    pub turn: f32,
+    // positive strafes right, only used by mouse aim
+    pub strafe: f32,
+    // cursor position relative to the ship, which turns to face it, only used by mouse aim.
+    // Relative so it holds steady while the ship moves, keeping recorded input compact
+    pub aim: Option<Vec2>,
+    pub scheme: ControlScheme,
+    pub thrust_space: ThrustSpace,
+    pub fire: bool,
+    // latched until the simulation handles it, so a press between ticks isn't lost
+    pub toggle_dampener: bool,
+    // latched, docks with a nearby station or undocks if already docked
+    pub dock: bool,
+    // latched, adds a waypoint to the autopilot route
+    pub waypoint: Option<WaypointOrder>,
 }
 
-const MOVEMENT_SPEED: f32 = 280.0;
+const MAX_SPEED: f32 = 280.0;
+const THRUST_ACCELERATION: f32 = 400.0;
+const REVERSE_THRUST_ACCELERATION: f32 = 200.0;
+const STRAFE_ACCELERATION: f32 = 200.0;
+// Turn speed per radian the ship is off from facing the cursor
+const AIM_TURN_RESPONSE: f32 = 8.0;
+const DRAG: f32 = 0.1;
+const INERTIAL_DAMPENER_STRENGTH: f32 = 2.5;
+const MASS: f32 = 1.0;
+const TURN_RATE: f32 = 20.0;
+const MAX_ANGULAR_SPEED: f32 = 4.5;
+const ANGULAR_DAMPING: f32 = 1.0;
+const STARTING_CREDITS: usize = 1000;
 const PROJECTILE_SPEED: f32 = 500.0;
+const PROJECTILE_VELOCITY_INHERITANCE: f32 = 1.0;
 const PROJECTILE_FORWARD_SPAWN_SCALAR: f32 = 20.0;
-const PROJECTILE_DESPAWN_TIME_SECONDS: f32 = 2.0;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::LoadingGame), spawn_player)
+        app.init_resource::<PlayerInput>()
+            .add_systems(
+                OnEnter(GameState::LoadingGame),
+                spawn_player.in_set(SessionSetupSet),
+            )
+            .add_systems(
+                PreUpdate,
+                read_player_input
+                    .after(InputActionSet)
+                    .after(CursorSet)
+                    .run_if(in_state(GameState::InGame)),
+            )
             .add_systems(
-                Update,
+                FixedUpdate,
                 (player_movement, player_weapon_controls)
                     .chain()
                     .in_set(InGameSet::UserInput),
-            )
-            .add_systems(
-                Update,
-                despawn_projectile.in_set(InGameSet::DespawnEntities),
             );
     }
 }
 
-fn spawn_player(mut commands: Commands, image_assets: Res<ImageAssets>) {
+fn spawn_player(
+    mut commands: Commands,
+    image_assets: Res<ImageAssets>,
+    mut player_input: ResMut<PlayerInput>,
+) {
     info!("Spawning Player");
 
+    // Don't carry input over from a previous session
+    *player_input = PlayerInput::default();
+
     // Add player sprite
     let player_transform: Transform = Transform::from_xyz(0., 0., 3.).with_scale(Vec3::splat(0.2));
 
@@ -52,92 +108,249 @@ fn spawn_player(mut commands: Commands, image_assets: Res<ImageAssets>) {
             },
         },
         Player,
-        Rotation::new(),
+        Bounded::new(BoundsClass::Player),
+        (
+            AngularVelocity::new(0.0),
+            AngularAcceleration::new(0.0),
+            AngularDamping::new(ANGULAR_DAMPING),
+            MaxAngularSpeed::new(MAX_ANGULAR_SPEED),
+            TurnRate::new(TURN_RATE),
+        ),
+        Mass::new(MASS),
+        Drag::new(DRAG),
+        MaxSpeed::new(MAX_SPEED),
+        InertialDampener::new(INERTIAL_DAMPENER_STRENGTH),
+        Weapon::new(
+            PROJECTILE_SPEED,
+            PROJECTILE_VELOCITY_INHERITANCE,
+            PROJECTILE_FORWARD_SPAWN_SCALAR,
+        ),
+        Autopilot::default(),
+        Wallet::new(STARTING_CREDITS),
+        Cargo::default(),
+        SessionScoped,
     ));
 }
 
-fn player_movement(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<(&Transform, &mut Velocity, &mut Rotation), With<Player>>,
+impl PlayerInput {
+    // Whether the player is flying the ship themselves
+    pub fn is_steering(&self) -> bool {
+        return self.thrust != 0.0 || self.turn != 0.0 || self.strafe != 0.0;
+    }
+}
+
+fn read_player_input(
+    actions: Res<ActionState>,
+    settings: Res<GameSettings>,
+    active_device: Res<ActiveInputDevice>,
+    cursor: Res<CursorWorldPosition>,
+    player_query: Query<&Transform, With<Player>>,
+    mut player_input: ResMut<PlayerInput>,
 ) {
-    for (transform, mut velocity, mut rotation) in query.iter_mut() {
-        let mut default_rotation_factor = 0.0;
-        let mut movement_factor = 0.0;
+    // Mouse aim needs the mouse, so gamepads always fly with tank controls
+    player_input.scheme = match active_device.device {
+        InputDevice::KeyboardMouse => settings.gameplay.control_scheme,
+        InputDevice::Gamepad(_) => ControlScheme::Tank,
+    };
+    player_input.thrust_space = settings.gameplay.thrust_space;
+    player_input.thrust = actions.axis(InputAction::Thrust, InputAction::Reverse);
+    player_input.fire = actions.pressed(InputAction::Fire);
 
-        if keyboard_input.pressed(KeyCode::A) {
-            default_rotation_factor += 1.0;
+    match player_input.scheme {
+        ControlScheme::Tank => {
+            player_input.turn = actions.axis(InputAction::TurnLeft, InputAction::TurnRight);
+            player_input.strafe = 0.0;
+            player_input.aim = None;
         }
-
-        if keyboard_input.pressed(KeyCode::D) {
-            default_rotation_factor -= 1.0;
+        ControlScheme::MouseAim => {
+            // the turn keys strafe instead, the cursor does the turning
+            player_input.turn = 0.0;
+            player_input.strafe = actions.axis(InputAction::TurnRight, InputAction::TurnLeft);
+            player_input.aim = cursor
+                .position
+                .zip(player_query.get_single().ok())
+                .map(|(position, transform)| position - transform.translation.truncate());
         }
+    }
 
-        if keyboard_input.pressed(KeyCode::W) {
-            movement_factor += 1.0;
-        }
+    if actions.just_pressed(InputAction::ToggleDampener) {
+        player_input.toggle_dampener = true;
+    }
 
-        rotation.factor = default_rotation_factor;
-
-        // get the ship's forward vector by applying the current rotation to the ships initial facing
-        // vector
-        let movement_direction = transform.rotation * Vec3::Y;
-        // get the distance the ship will move based on direction, the ship's movement speed and delta
-        // time
-        let movement_distance = movement_factor * MOVEMENT_SPEED;
-        // create the change in translation using the new movement direction and distance
-        let translation_delta = movement_direction * movement_distance;
-        // update the ship translation with our new translation delta
-        velocity.value = translation_delta;
+    if actions.just_pressed(InputAction::Dock) {
+        player_input.dock = true;
     }
 }
 
-fn player_weapon_controls(
-    mut commands: Commands,
-    query: Query<&Transform, With<Player>>,
-    keyboard_input: Res<Input<KeyCode>>,
-    image_assets: Res<ImageAssets>,
+pub fn player_movement(
+    mut player_input: ResMut<PlayerInput>,
+    mut query: Query<
+        (
+            &Transform,
+            &TurnRate,
+            &Mass,
+            &AngularVelocity,
+            &MaxAngularSpeed,
+            &mut Acceleration,
+            &mut AngularAcceleration,
+            &mut InertialDampener,
+            Option<&Autopilot>,
+        ),
+        With<Player>,
+    >,
 ) {
-    let Ok(transform) = query.get_single() else {
-        return info!("Error trying to get Player Transform");
-    };
+    for (
+        transform,
+        turn_rate,
+        mass,
+        angular_velocity,
+        max_angular_speed,
+        mut acceleration,
+        mut angular_acceleration,
+        mut dampener,
+        autopilot,
+    ) in query.iter_mut()
+    {
+        if player_input.toggle_dampener {
+            dampener.enabled = !dampener.enabled;
+            info!("Inertial dampener enabled: {}", dampener.enabled);
+        }
 
-    if keyboard_input.pressed(KeyCode::Space) {
-        // calculate where to spawn the projectile (in front of player)
-        let transform_vec: Vec3 =
-            transform.translation + transform.up() * PROJECTILE_FORWARD_SPAWN_SCALAR;
-        let mut projectile_transform: Transform = Transform::from_translation(transform_vec);
-        projectile_transform.scale = Vec3::new(0.03, 0.03, 0.);
-
-        commands.spawn((
-            MovingObjectBundle {
-                velocity: Velocity::new(transform.up() * PROJECTILE_SPEED),
-                acceleration: Acceleration::new(Vec3::ZERO),
-                sprite: SpriteBundle {
-                    texture: image_assets.projectile.clone(),
-                    transform: projectile_transform,
-                    ..default()
-                },
-            },
-            Projectile {
-                despawn_timer: Timer::from_seconds(
-                    PROJECTILE_DESPAWN_TIME_SECONDS,
-                    TimerMode::Once,
+        // the autopilot flies the ship with the same controls as the player
+        let (thrust, angular) = match autopilot {
+            Some(autopilot) if autopilot.is_engaged() => {
+                tank_controls(transform, turn_rate, mass, autopilot.thrust, autopilot.turn)
+            }
+            _ => match player_input.scheme {
+                ControlScheme::Tank => tank_controls(
+                    transform,
+                    turn_rate,
+                    mass,
+                    player_input.thrust,
+                    player_input.turn,
+                ),
+                ControlScheme::MouseAim => mouse_aim_controls(
+                    transform,
+                    turn_rate,
+                    mass,
+                    angular_velocity,
+                    max_angular_speed,
+                    &player_input,
                 ),
             },
-        ));
+        };
+
+        // thrust is applied as acceleration, velocity and position are integrated in movement
+        acceleration.value = thrust;
+        angular_acceleration.value = angular;
     }
+
+    player_input.toggle_dampener = false;
+}
+
+// Thrust along the ship's facing and turn input spins it, returns the linear and angular acceleration
+fn tank_controls(
+    transform: &Transform,
+    turn_rate: &TurnRate,
+    mass: &Mass,
+    thrust_input: f32,
+    turn_input: f32,
+) -> (Vec3, f32) {
+    let thrust = if thrust_input > 0.0 {
+        thrust_input * THRUST_ACCELERATION
+    } else {
+        thrust_input * REVERSE_THRUST_ACCELERATION
+    };
+
+    // get the ship's forward vector by applying the current rotation to the ships initial facing
+    // vector
+    let thrust_direction = transform.rotation * Vec3::Y;
+
+    return (
+        thrust_direction * thrust,
+        turn_rate.angular_acceleration(turn_input, mass),
+    );
+}
+
+// The ship turns to face the aim point, and thrust moves it relative to the ship or the world
+fn mouse_aim_controls(
+    transform: &Transform,
+    turn_rate: &TurnRate,
+    mass: &Mass,
+    angular_velocity: &AngularVelocity,
+    max_angular_speed: &MaxAngularSpeed,
+    player_input: &PlayerInput,
+) -> (Vec3, f32) {
+    let forward: Vec2 = transform.up().truncate();
+    let mut turn_input: f32 = 0.0;
+
+    if let Some(to_aim) = player_input.aim {
+        if to_aim != Vec2::ZERO {
+            let angle: f32 = forward.angle_between(to_aim);
+
+            // turn towards the cursor, easing off as the ship's rotation lines up
+            let desired_angular_velocity: f32 = (angle * AIM_TURN_RESPONSE)
+                .clamp(-max_angular_speed.value, max_angular_speed.value);
+            turn_input = (desired_angular_velocity - angular_velocity.value).clamp(-1.0, 1.0);
+        }
+    }
+
+    let thrust: Vec3 = match player_input.thrust_space {
+        ThrustSpace::Ship => {
+            let forward_thrust: f32 = if player_input.thrust > 0.0 {
+                player_input.thrust * THRUST_ACCELERATION
+            } else {
+                player_input.thrust * REVERSE_THRUST_ACCELERATION
+            };
+            let local: Vec3 = Vec3::new(
+                player_input.strafe * STRAFE_ACCELERATION,
+                forward_thrust,
+                0.0,
+            );
+
+            transform.rotation * local
+        }
+        ThrustSpace::World => {
+            // keep diagonals from being faster than a single direction
+            let direction: Vec2 =
+                Vec2::new(player_input.strafe, player_input.thrust).clamp_length_max(1.0);
+
+            direction.extend(0.0) * THRUST_ACCELERATION
+        }
+    };
+
+    return (thrust, turn_rate.angular_acceleration(turn_input, mass));
 }
 
-fn despawn_projectile(
+fn player_weapon_controls(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut Projectile), With<Projectile>>,
+    query: Query<(&Transform, &Velocity, &Weapon), (With<Player>, Without<Docked>)>,
+    mut projectiles: ProjectileQuery,
+    mut pool: ResMut<ProjectilePool>,
+    player_input: Res<PlayerInput>,
+    image_assets: Res<ImageAssets>,
+    mut event_writer: EventWriter<OnWeaponFired>,
     time: Res<Time>,
 ) {
-    for (entity, mut projectile) in query.iter_mut() {
-        projectile.despawn_timer.tick(time.delta());
+    // No firing while docked
+    let Ok((transform, velocity, weapon)) = query.get_single() else {
+        return;
+    };
 
-        if projectile.despawn_timer.just_finished() {
-            commands.entity(entity).despawn_recursive();
-        }
+    if player_input.fire {
+        // calculate where to spawn the projectile (in front of player)
+        let transform_vec: Vec3 =
+            weapon.spawn_position(transform, velocity.value, time.delta_seconds());
+
+        fire_projectile(
+            &mut commands,
+            &mut pool,
+            &mut projectiles,
+            &image_assets,
+            transform_vec,
+            weapon.projectile_velocity(transform.up(), velocity.value),
+        );
+
+        event_writer.send(OnWeaponFired);
     }
 }
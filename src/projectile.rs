@@ -0,0 +1,247 @@
+use crate::asset_loader::ImageAssets;
+use crate::movement::{
+    Acceleration, IgnoresGravity, MovingObjectBundle, TransformInterpolation, Velocity,
+};
+use crate::player::Player;
+use crate::schedule::InGameSet;
+use crate::state::{GameState, SessionScoped};
+use crate::world_bounds::{Bounded, BoundsClass};
+use bevy::prelude::*;
+
+const PROJECTILE_POOL_SIZE: usize = 256;
+const PROJECTILE_DESPAWN_TIME_SECONDS: f32 = 2.0;
+const PROJECTILE_SCALE: Vec3 = Vec3::new(0.03, 0.03, 0.);
+
+#[derive(Component, Debug)]
+pub struct Projectile {
+    pub despawn_timer: Timer,
+    // Pooled projectiles stay alive, inactive ones are hidden and waiting to be reused
+    pub active: bool,
+}
+
+#[derive(Component, Debug)]
+pub struct Weapon {
+    pub projectile_speed: f32,
+    // fraction of the shooter's velocity added to each projectile
+    pub velocity_inheritance: f32,
+    // distance in front of the shooter projectiles spawn at
+    pub spawn_offset: f32,
+}
+
+impl Weapon {
+    pub fn new(projectile_speed: f32, velocity_inheritance: f32, spawn_offset: f32) -> Self {
+        Self {
+            projectile_speed,
+            velocity_inheritance,
+            spawn_offset,
+        }
+    }
+
+    pub fn projectile_velocity(&self, forward: Vec3, shooter_velocity: Vec3) -> Vec3 {
+        return forward * self.projectile_speed + shooter_velocity * self.velocity_inheritance;
+    }
+
+    pub fn spawn_position(
+        &self,
+        shooter: &Transform,
+        shooter_velocity: Vec3,
+        delta_seconds: f32,
+    ) -> Vec3 {
+        // spawn ahead of where the shooter will be after this tick, so a fast ship doesn't
+        // fly into its own projectiles
+        return shooter.translation
+            + shooter.up() * self.spawn_offset
+            + shooter_velocity * delta_seconds;
+    }
+}
+
+// Holds projectile entities that are not in use so they can be recycled
+// instead of being spawned and despawned for every shot
+#[derive(Resource, Debug, Default)]
+pub struct ProjectilePool {
+    free: Vec<Entity>,
+}
+
+impl ProjectilePool {
+    // Deactivates a projectile and hands it back to the pool
+    pub fn release(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        projectile: &mut Projectile,
+        velocity: &mut Velocity,
+        visibility: &mut Visibility,
+    ) {
+        projectile.active = false;
+        velocity.value = Vec3::ZERO;
+        *visibility = Visibility::Hidden;
+        // keep it parked where it is until it's fired again
+        commands.entity(entity).insert(IgnoresGravity);
+        self.free.push(entity);
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct OnWeaponFired;
+
+// Projectiles that can be reused by `fire_projectile`, excludes the player to keep its
+// `Transform` access disjoint
+pub type ProjectileQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Projectile,
+        &'static mut Transform,
+        &'static mut Velocity,
+        &'static mut Visibility,
+        Option<&'static mut TransformInterpolation>,
+    ),
+    Without<Player>,
+>;
+
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ProjectilePool>()
+            .add_event::<OnWeaponFired>()
+            .add_systems(OnEnter(GameState::LoadingGame), fill_projectile_pool)
+            .add_systems(
+                FixedUpdate,
+                recycle_projectile.in_set(InGameSet::DespawnEntities),
+            );
+    }
+}
+
+fn fill_projectile_pool(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    image_assets: Res<ImageAssets>,
+) {
+    info!("Filling projectile pool");
+
+    // Entities from a previous session have been despawned
+    pool.free.clear();
+
+    while pool.free.len() < PROJECTILE_POOL_SIZE {
+        let entity: Entity = spawn_projectile(
+            &mut commands,
+            &image_assets,
+            Transform::default(),
+            Vec3::ZERO,
+            false,
+        );
+        pool.free.push(entity);
+    }
+}
+
+pub fn spawn_projectile(
+    commands: &mut Commands,
+    image_assets: &ImageAssets,
+    transform: Transform,
+    velocity: Vec3,
+    active: bool,
+) -> Entity {
+    let visibility: Visibility = if active {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    let entity: Entity = commands
+        .spawn((
+            MovingObjectBundle {
+                velocity: Velocity::new(velocity),
+                acceleration: Acceleration::new(Vec3::ZERO),
+                sprite: SpriteBundle {
+                    texture: image_assets.projectile.clone(),
+                    transform: transform.with_scale(PROJECTILE_SCALE),
+                    visibility,
+                    ..default()
+                },
+            },
+            Projectile {
+                despawn_timer: Timer::from_seconds(
+                    PROJECTILE_DESPAWN_TIME_SECONDS,
+                    TimerMode::Once,
+                ),
+                active,
+            },
+            Bounded::new(BoundsClass::Projectile),
+            SessionScoped,
+        ))
+        .id();
+
+    if !active {
+        commands.entity(entity).insert(IgnoresGravity);
+    }
+
+    return entity;
+}
+
+// Activates a projectile from the pool, only spawning a new one when the pool is empty
+pub fn fire_projectile(
+    commands: &mut Commands,
+    pool: &mut ProjectilePool,
+    projectiles: &mut ProjectileQuery,
+    image_assets: &ImageAssets,
+    translation: Vec3,
+    velocity: Vec3,
+) {
+    let transform: Transform = Transform::from_translation(translation);
+
+    while let Some(entity) = pool.free.pop() {
+        // Entity may have been despawned elsewhere, skip it if so
+        let Ok((
+            mut projectile,
+            mut projectile_transform,
+            mut projectile_velocity,
+            mut visibility,
+            interpolation,
+        )) = projectiles.get_mut(entity)
+        else {
+            continue;
+        };
+
+        projectile.active = true;
+        projectile.despawn_timer.reset();
+        projectile_transform.translation = translation;
+        projectile_velocity.value = velocity;
+        *visibility = Visibility::Visible;
+        commands.entity(entity).remove::<IgnoresGravity>();
+
+        // don't render the projectile sliding over from where it was last used
+        if let Some(mut interpolation) = interpolation {
+            interpolation.teleport(*projectile_transform);
+        }
+
+        return;
+    }
+
+    spawn_projectile(commands, image_assets, transform, velocity, true);
+}
+
+fn recycle_projectile(
+    mut commands: Commands,
+    mut pool: ResMut<ProjectilePool>,
+    mut query: Query<(Entity, &mut Projectile, &mut Velocity, &mut Visibility)>,
+    time: Res<Time>,
+) {
+    for (entity, mut projectile, mut velocity, mut visibility) in query.iter_mut() {
+        if !projectile.active {
+            continue;
+        }
+
+        projectile.despawn_timer.tick(time.delta());
+
+        if projectile.despawn_timer.just_finished() {
+            pool.release(
+                &mut commands,
+                entity,
+                &mut projectile,
+                &mut velocity,
+                &mut visibility,
+            );
+        }
+    }
+}
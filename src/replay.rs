@@ -0,0 +1,636 @@
+use crate::enemy::SpawnTimer;
+use crate::player::{Player, PlayerInput};
+use crate::save_game::PendingLoad;
+use crate::schedule::{GameClock, InGameSet, SimulationSeed, SimulationSeedOverride};
+use crate::settings::{Difficulty, GameSettings};
+use crate::state::{GameState, OnGameStart, SessionSetupSet};
+use crate::util::data_directory;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const RECORDING_VERSION: u32 = 2;
+const RECORDING_DIRECTORY: &str = "recordings";
+const RECORDING_EXTENSION: &str = "replay";
+// Older recordings are deleted when a new one starts
+const MAX_RECORDINGS: usize = 20;
+// Command line flag followed by the path of a recording to play back
+const REPLAY_ARGUMENT: &str = "--replay";
+// Analog input is rounded to these steps before the tick uses it, so jitter too small to
+// matter doesn't break up runs of input
+const AXIS_STEP: f32 = 1.0 / 32.0;
+const AIM_STEP: f32 = 1.0;
+
+// What's needed to start a session off the same way as the recorded one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordingHeader {
+    version: u32,
+    seed: u64,
+    difficulty: Difficulty,
+}
+
+// Read on its own first so recordings from other versions fail with a clear message
+#[derive(Debug, Deserialize)]
+struct RecordingVersion {
+    version: u32,
+}
+
+// A stretch of ticks that all saw the same player input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct InputRun {
+    ticks: u32,
+    input: PlayerInput,
+}
+
+impl InputRun {
+    fn new(input: &PlayerInput) -> Self {
+        Self {
+            ticks: 1,
+            input: input.clone(),
+        }
+    }
+
+    // Adds a tick to the run, false if the input differs and a new run is needed
+    fn extend(&mut self, input: &PlayerInput) -> bool {
+        if self.input != *input {
+            return false;
+        }
+
+        self.ticks += 1;
+
+        return true;
+    }
+}
+
+// Player input for every simulation tick of a session. Input is stored per tick rather than
+// per rendered frame so playback doesn't depend on frame timing.
+//
+// Saved as the header on the first line followed by one run per line, so it can be appended
+// to as the session is played
+#[derive(Debug, Clone)]
+struct Recording {
+    header: RecordingHeader,
+    runs: Vec<InputRun>,
+}
+
+impl Recording {
+    fn push(&mut self, input: &PlayerInput) {
+        if let Some(run) = self.runs.last_mut() {
+            if run.extend(input) {
+                return;
+            }
+        }
+
+        self.runs.push(InputRun::new(input));
+    }
+
+    fn tick_count(&self) -> u64 {
+        return self.runs.iter().map(|run| run.ticks as u64).sum();
+    }
+
+    fn parse(contents: &str) -> Result<Self, String> {
+        let mut lines: std::str::Lines = contents.lines();
+
+        let Some(header_line) = lines.next() else {
+            return Err("recording is empty".to_string());
+        };
+
+        let version: RecordingVersion = ron::from_str(header_line)
+            .map_err(|error| format!("couldn't parse header: {}", error))?;
+
+        if version.version != RECORDING_VERSION {
+            return Err(format!(
+                "recording is version {}, expected {}",
+                version.version, RECORDING_VERSION
+            ));
+        }
+
+        let header: RecordingHeader = ron::from_str(header_line)
+            .map_err(|error| format!("couldn't parse header: {}", error))?;
+
+        let mut runs: Vec<InputRun> = Vec::new();
+
+        for line in lines {
+            match ron::from_str(line) {
+                Ok(run) => runs.push(run),
+                Err(error) => {
+                    // The last line is cut short if the game stopped while writing it
+                    warn!("Recording ends early, {}", error);
+                    break;
+                }
+            }
+        }
+
+        return Ok(Self { header, runs });
+    }
+
+    fn read(path: &Path) -> Result<Self, String> {
+        let contents: String = fs::read_to_string(path)
+            .map_err(|error| format!("couldn't read {}: {}", path.display(), error))?;
+
+        return Self::parse(&contents).map_err(|error| format!("{}: {}", path.display(), error));
+    }
+}
+
+// Appends lines to a recording on its own thread, so disk writes never hold up a frame
+#[derive(Debug)]
+struct RecordingWriter {
+    sender: Option<Sender<String>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RecordingWriter {
+    fn start(path: PathBuf, header: &RecordingHeader) -> Self {
+        let (sender, receiver) = mpsc::channel::<String>();
+        let thread: JoinHandle<()> = thread::spawn(move || write_recording(path, receiver));
+
+        let writer: Self = Self {
+            sender: Some(sender),
+            thread: Some(thread),
+        };
+        writer.send(header);
+
+        return writer;
+    }
+
+    fn send<T: Serialize>(&self, value: &T) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        match ron::to_string(value) {
+            Ok(line) => {
+                // Only fails if the writer thread has given up, which it reports itself
+                let _ = sender.send(line);
+            }
+            Err(error) => warn!("Failed to serialise input recording, {}", error),
+        }
+    }
+
+    // Waits for everything sent so far to be written
+    fn finish(&mut self) {
+        self.sender = None;
+
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                warn!("Input recording writer stopped unexpectedly");
+            }
+        }
+    }
+}
+
+fn write_recording(path: PathBuf, receiver: Receiver<String>) {
+    if let Some(directory) = path.parent() {
+        if let Err(error) = fs::create_dir_all(directory) {
+            return warn!("Couldn't create {}, {}", directory.display(), error);
+        }
+
+        prune_recordings(directory);
+    }
+
+    let file: fs::File = match fs::File::create(&path) {
+        Ok(file) => file,
+        Err(error) => return warn!("Couldn't create {}, {}", path.display(), error),
+    };
+    let mut writer: BufWriter<fs::File> = BufWriter::new(file);
+
+    while let Ok(line) = receiver.recv() {
+        // Write out everything that's queued up before flushing
+        let result: std::io::Result<()> = std::iter::once(line)
+            .chain(receiver.try_iter())
+            .try_for_each(|line| writeln!(writer, "{}", line))
+            .and_then(|_| writer.flush());
+
+        if let Err(error) = result {
+            return warn!("Couldn't write {}, {}", path.display(), error);
+        }
+    }
+}
+
+// Makes room for a new recording by deleting the oldest ones
+fn prune_recordings(directory: &Path) {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    let mut recordings: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == RECORDING_EXTENSION)
+        })
+        .collect();
+
+    if recordings.len() < MAX_RECORDINGS {
+        return;
+    }
+
+    // Named by timestamp, so oldest first
+    recordings.sort();
+
+    for path in &recordings[..=recordings.len() - MAX_RECORDINGS] {
+        if let Err(error) = fs::remove_file(path) {
+            warn!(
+                "Couldn't delete old recording {}, {}",
+                path.display(),
+                error
+            );
+        }
+    }
+}
+
+// Records the current session, streamed to the recordings directory as it's played
+#[derive(Resource, Debug)]
+struct InputRecorder {
+    // only holds the run still being added to, earlier ones have been written out
+    recording: Recording,
+    writer: RecordingWriter,
+}
+
+impl InputRecorder {
+    fn start(path: PathBuf, header: RecordingHeader) -> Self {
+        return Self {
+            writer: RecordingWriter::start(path, &header),
+            recording: Recording {
+                header,
+                runs: Vec::new(),
+            },
+        };
+    }
+
+    fn push(&mut self, input: &PlayerInput) {
+        self.recording.push(input);
+
+        let finished: usize = self.recording.runs.len().saturating_sub(1);
+
+        for run in self.recording.runs.drain(..finished) {
+            self.writer.send(&run);
+        }
+    }
+
+    fn finish(&mut self) {
+        for run in self.recording.runs.drain(..) {
+            self.writer.send(&run);
+        }
+
+        self.writer.finish();
+    }
+}
+
+// Plays a recording back in place of live input, removed once the recording runs out
+#[derive(Resource, Debug)]
+pub struct InputReplay {
+    recording: Recording,
+    run: usize,
+    ticks_into_run: u32,
+}
+
+impl InputReplay {
+    fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            run: 0,
+            ticks_into_run: 0,
+        }
+    }
+
+    // Input for the next tick, `None` once the recording is over
+    fn next_input(&mut self) -> Option<PlayerInput> {
+        while let Some(run) = self.recording.runs.get(self.run) {
+            if self.ticks_into_run < run.ticks {
+                self.ticks_into_run += 1;
+                return Some(run.input.clone());
+            }
+
+            self.run += 1;
+            self.ticks_into_run = 0;
+        }
+
+        return None;
+    }
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::LoadingGame),
+            (start_recording, apply_replay_difficulty).after(SessionSetupSet),
+        )
+        .add_systems(
+            FixedUpdate,
+            // Before anything reads the tick's input
+            (
+                replay_input.run_if(resource_exists::<InputReplay>()),
+                record_input.run_if(resource_exists::<InputRecorder>()),
+            )
+                .chain()
+                .after(InGameSet::DespawnEntities)
+                .before(InGameSet::UserInput)
+                .run_if(in_state(GameState::InGame)),
+        )
+        .add_systems(
+            OnEnter(GameState::StartMenu),
+            (
+                finish_recording,
+                stop_replay,
+                // The first time the start menu opens, after the game has set up
+                start_replay_from_arguments.run_if(run_once()),
+            )
+                .chain(),
+        )
+        .add_systems(
+            Last,
+            flush_recording
+                .run_if(resource_exists::<InputRecorder>())
+                .run_if(on_event::<AppExit>()),
+        );
+    }
+}
+
+fn recording_path() -> Option<PathBuf> {
+    let timestamp: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    return data_directory().map(|directory| {
+        directory
+            .join(RECORDING_DIRECTORY)
+            .join(format!("{}.{}", timestamp, RECORDING_EXTENSION))
+    });
+}
+
+fn quantise(value: f32, step: f32) -> f32 {
+    return (value / step).round() * step;
+}
+
+// Rounds analog input in place, so the tick runs on exactly what gets recorded
+fn quantise_input(input: &mut PlayerInput) {
+    input.thrust = quantise(input.thrust, AXIS_STEP);
+    input.turn = quantise(input.turn, AXIS_STEP);
+    input.strafe = quantise(input.strafe, AXIS_STEP);
+    input.aim = input
+        .aim
+        .map(|aim| Vec2::new(quantise(aim.x, AIM_STEP), quantise(aim.y, AIM_STEP)));
+}
+
+fn start_replay_from_arguments(mut commands: Commands, mut event_writer: EventWriter<OnGameStart>) {
+    let Some(path) = std::env::args()
+        .skip_while(|argument| argument != REPLAY_ARGUMENT)
+        .nth(1)
+    else {
+        return;
+    };
+
+    let recording: Recording = match Recording::read(Path::new(&path)) {
+        Ok(recording) => recording,
+        Err(error) => return error!("Failed to load replay, {}", error),
+    };
+
+    info!(
+        "Replaying {} ticks of input from {}",
+        recording.tick_count(),
+        path
+    );
+
+    // Start a new session straight away, seeded the same as the recorded one
+    commands.insert_resource(SimulationSeedOverride(recording.header.seed));
+    commands.insert_resource(InputReplay::new(recording));
+    event_writer.send(OnGameStart);
+}
+
+fn apply_replay_difficulty(replay: Option<Res<InputReplay>>, mut spawn_timer: ResMut<SpawnTimer>) {
+    let Some(replay) = replay else {
+        return;
+    };
+
+    // Enemy spawn rate depends on difficulty, so match the recorded session
+    spawn_timer.set_interval_scale(replay.recording.header.difficulty.spawn_interval_scale());
+}
+
+fn start_recording(
+    mut commands: Commands,
+    seed: Res<SimulationSeed>,
+    settings: Res<GameSettings>,
+    replay: Option<Res<InputReplay>>,
+    pending_load: Option<Res<PendingLoad>>,
+) {
+    if replay.is_some() {
+        return;
+    }
+
+    // Replays always start from a new game
+    if pending_load.is_some() {
+        return info!("Not recording input, sessions loaded from a save can't be replayed");
+    }
+
+    let Some(path) = recording_path() else {
+        return warn!("Not recording input, no data directory");
+    };
+
+    info!("Recording input to {}", path.display());
+
+    // The session has already been seeded by `SessionSetupSet`
+    let header: RecordingHeader = RecordingHeader {
+        version: RECORDING_VERSION,
+        seed: seed.0,
+        difficulty: settings.gameplay.difficulty,
+    };
+
+    commands.insert_resource(InputRecorder::start(path, header));
+}
+
+fn replay_input(
+    mut commands: Commands,
+    mut replay: ResMut<InputReplay>,
+    mut player_input: ResMut<PlayerInput>,
+    clock: Res<GameClock>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    if let Some(input) = replay.next_input() {
+        *player_input = input;
+        return;
+    }
+
+    // Log where things ended up so runs can be compared against each other
+    let position: Option<Vec3> = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation);
+    info!(
+        "Replay finished at {:.2}s, player at {:?}",
+        clock.elapsed_seconds, position
+    );
+
+    // Hand control back to the player
+    *player_input = PlayerInput::default();
+    commands.remove_resource::<InputReplay>();
+}
+
+fn record_input(mut recorder: ResMut<InputRecorder>, mut player_input: ResMut<PlayerInput>) {
+    quantise_input(&mut player_input);
+    recorder.push(&player_input);
+}
+
+fn flush_recording(mut recorder: ResMut<InputRecorder>) {
+    recorder.finish();
+}
+
+fn finish_recording(mut commands: Commands, recorder: Option<ResMut<InputRecorder>>) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+
+    recorder.finish();
+    commands.remove_resource::<InputRecorder>();
+}
+
+fn stop_replay(mut commands: Commands) {
+    commands.remove_resource::<InputReplay>();
+    // Sessions after the replay pick their own seed again
+    commands.remove_resource::<SimulationSeedOverride>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{run_ticks, simulation_app, start_session};
+
+    const THRUST_THEN_TURN: &str = include_str!("../tests/recordings/thrust_then_turn.replay");
+
+    fn input(thrust: f32) -> PlayerInput {
+        return PlayerInput {
+            thrust,
+            ..default()
+        };
+    }
+
+    fn recording(runs: Vec<InputRun>) -> Recording {
+        return Recording {
+            header: RecordingHeader {
+                version: RECORDING_VERSION,
+                seed: 0,
+                difficulty: Difficulty::Normal,
+            },
+            runs,
+        };
+    }
+
+    // Plays the recording through the simulation, returning where the player ended up
+    fn replay(recording: Recording) -> Transform {
+        let mut app: App = simulation_app(recording.header.seed);
+        app.add_plugins(ReplayPlugin);
+
+        let ticks: u32 = recording.tick_count() as u32;
+        app.insert_resource(InputReplay::new(recording));
+
+        start_session(&mut app);
+        // One more tick for the replay to notice it's over
+        run_ticks(&mut app, ticks + 1);
+
+        assert!(!app.world.contains_resource::<InputReplay>());
+
+        let mut query: QueryState<&Transform, With<Player>> = app.world.query_filtered();
+
+        return *query.single(&app.world);
+    }
+
+    #[test]
+    fn push_starts_a_run_when_input_changes() {
+        let mut recording: Recording = recording(Vec::new());
+
+        for thrust in [1.0, 1.0, 0.0, 0.0, 0.0, 1.0] {
+            recording.push(&input(thrust));
+        }
+
+        let runs: Vec<(u32, f32)> = recording
+            .runs
+            .iter()
+            .map(|run| (run.ticks, run.input.thrust))
+            .collect();
+
+        assert_eq!(runs, vec![(2, 1.0), (3, 0.0), (1, 1.0)]);
+        assert_eq!(recording.tick_count(), 6);
+    }
+
+    #[test]
+    fn quantised_jitter_stays_in_one_run() {
+        let mut recording: Recording = recording(Vec::new());
+
+        for (thrust, aim) in [(0.5, Vec2::new(10.2, -3.9)), (0.501, Vec2::new(9.8, -4.1))] {
+            let mut input: PlayerInput = PlayerInput {
+                thrust,
+                aim: Some(aim),
+                ..default()
+            };
+            quantise_input(&mut input);
+            recording.push(&input);
+        }
+
+        assert_eq!(recording.runs.len(), 1);
+        assert_eq!(recording.runs[0].input.aim, Some(Vec2::new(10.0, -4.0)));
+    }
+
+    #[test]
+    fn next_input_steps_across_run_boundaries() {
+        let mut replay: InputReplay = InputReplay::new(recording(vec![
+            InputRun {
+                ticks: 2,
+                input: input(1.0),
+            },
+            // Never produced by recording, but a hand written file could have one
+            InputRun {
+                ticks: 0,
+                input: input(0.5),
+            },
+            InputRun {
+                ticks: 1,
+                input: input(-1.0),
+            },
+        ]));
+
+        let thrusts: Vec<f32> = std::iter::from_fn(|| replay.next_input())
+            .map(|input| input.thrust)
+            .collect();
+
+        assert_eq!(thrusts, vec![1.0, 1.0, -1.0]);
+        assert!(replay.next_input().is_none());
+    }
+
+    #[test]
+    fn parse_stops_at_a_truncated_run() {
+        let contents: String = format!("{}(ticks:5,input:(thr", THRUST_THEN_TURN);
+        let recording: Recording = Recording::parse(&contents).unwrap();
+
+        assert_eq!(recording.header.seed, 1);
+        assert_eq!(recording.runs.len(), 3);
+        assert_eq!(recording.tick_count(), 120);
+    }
+
+    #[test]
+    fn parse_rejects_other_versions() {
+        let contents: String = THRUST_THEN_TURN.replacen("version:2", "version:1", 1);
+
+        assert!(Recording::parse(&contents).is_err());
+    }
+
+    #[test]
+    fn recording_replays_to_the_same_end_state() {
+        let first: Transform = replay(Recording::parse(THRUST_THEN_TURN).unwrap());
+        let second: Transform = replay(Recording::parse(THRUST_THEN_TURN).unwrap());
+
+        assert_eq!(first, second);
+
+        // Flew forward along +y, then turned left without thrusting
+        assert!(first.translation.y > 100.0, "{:?}", first.translation);
+        assert!(first.translation.x.abs() < 0.001, "{:?}", first.translation);
+        assert!((first.rotation * Vec3::Y).x < -0.1, "{:?}", first.rotation);
+    }
+}
@@ -0,0 +1,575 @@
+use crate::asset_loader::ImageAssets;
+use crate::docking::{dock_entity, Docked, OnDocked};
+use crate::enemy::{spawn_enemy_entity, Enemy};
+use crate::item_manager::{Cargo, Inventory, Wallet};
+use crate::level_manager::Station;
+use crate::movement::{Acceleration, AngularVelocity, InertialDampener, Velocity};
+use crate::player::Player;
+use crate::replay::InputReplay;
+use crate::schedule::GameClock;
+use crate::state::{GameState, OnGameStart, OnSaveGame, SessionSetupSet};
+use crate::util::data_directory;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Bump whenever `SaveGame` changes in a way older files can't be read as
+const SAVE_VERSION: u32 = 1;
+const SAVE_DIRECTORY: &str = "saves";
+const SAVE_EXTENSION: &str = "ron";
+const TEMP_EXTENSION: &str = "ron.tmp";
+pub const AUTOSAVE_SLOT: &str = "autosave";
+const MANUAL_SLOT_COUNT: usize = 5;
+const AUTOSAVE_INTERVAL_SECONDS: f32 = 120.0;
+// The player counts as near a station within this distance when describing their location
+const LOCATION_RANGE: f32 = 600.0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerSave {
+    pub transform: Transform,
+    pub velocity: Vec3,
+    pub angular_velocity: f32,
+    pub inertial_dampener: bool,
+    pub credits: usize,
+    pub cargo: Inventory,
+    // name of the station the player was docked at, saves from before this was added are undocked
+    #[serde(default)]
+    pub docked_station: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StationSave {
+    pub name: String,
+    pub inventory: Inventory,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnemySave {
+    pub transform: Transform,
+    pub velocity: Vec3,
+    pub acceleration: Vec3,
+    pub despawn_timer: Timer,
+}
+
+// Shown on the save slot list without restoring the save
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveMetadata {
+    pub name: String,
+    // seconds since the unix epoch
+    pub timestamp: u64,
+    pub location: String,
+    pub credits: usize,
+    pub playtime_seconds: f64,
+}
+
+// Everything needed to restore a session
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    // older saves without metadata still load, they just show blank details
+    #[serde(default)]
+    pub metadata: SaveMetadata,
+    pub player: PlayerSave,
+    pub stations: Vec<StationSave>,
+    pub enemies: Vec<EnemySave>,
+    pub clock_seconds: f64,
+}
+
+// Read before the rest of the file so a different version gives a clear error
+// instead of whatever field happened to fail to parse
+#[derive(Debug, Deserialize)]
+struct SaveVersion {
+    version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct SaveSummary {
+    #[serde(default)]
+    metadata: SaveMetadata,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    NoDataDirectory,
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+    VersionMismatch { found: u32, expected: u32 },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::NoDataDirectory => write!(f, "couldn't find the user data directory"),
+            SaveError::Io(error) => write!(f, "{}", error),
+            SaveError::Serialize(error) => write!(f, "couldn't write save data: {}", error),
+            SaveError::Parse(error) => write!(f, "save file is corrupted: {}", error),
+            SaveError::VersionMismatch { found, expected } => write!(
+                f,
+                "save file is from version {} but this game reads version {}",
+                found, expected
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(error: std::io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+impl From<ron::Error> for SaveError {
+    fn from(error: ron::Error) -> Self {
+        SaveError::Serialize(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SaveError::Parse(error)
+    }
+}
+
+// Ids of every save slot, the autosave first
+pub fn slot_ids() -> Vec<String> {
+    let mut slots: Vec<String> = vec![AUTOSAVE_SLOT.to_string()];
+
+    for index in 1..=MANUAL_SLOT_COUNT {
+        slots.push(format!("slot_{}", index));
+    }
+
+    return slots;
+}
+
+fn default_slot_name(slot: &str) -> String {
+    if slot == AUTOSAVE_SLOT {
+        return "Autosave".to_string();
+    }
+
+    return match slot.strip_prefix("slot_") {
+        Some(index) => format!("Slot {}", index),
+        None => slot.to_string(),
+    };
+}
+
+pub fn slot_path(slot: &str) -> Result<PathBuf, SaveError> {
+    let directory: PathBuf = data_directory().ok_or(SaveError::NoDataDirectory)?;
+
+    return Ok(directory
+        .join(SAVE_DIRECTORY)
+        .join(slot)
+        .with_extension(SAVE_EXTENSION));
+}
+
+// Writes to a temporary file first and renames it over the save, so a crash part way
+// through leaves the previous save intact
+pub fn write_save(path: &Path, save: &SaveGame) -> Result<(), SaveError> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    let contents: String = ron::ser::to_string_pretty(save, ron::ser::PrettyConfig::default())?;
+    let temp_path: PathBuf = path.with_extension(TEMP_EXTENSION);
+
+    let mut file: fs::File = fs::File::create(&temp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+
+    return Ok(());
+}
+
+pub fn read_save(path: &Path) -> Result<SaveGame, SaveError> {
+    let contents: String = fs::read_to_string(path)?;
+    check_version(&contents)?;
+
+    return Ok(ron::from_str(&contents)?);
+}
+
+fn check_version(contents: &str) -> Result<(), SaveError> {
+    let header: SaveVersion = ron::from_str(contents)?;
+
+    if header.version != SAVE_VERSION {
+        return Err(SaveError::VersionMismatch {
+            found: header.version,
+            expected: SAVE_VERSION,
+        });
+    }
+
+    return Ok(());
+}
+
+// Metadata for the save in a slot, `None` if the slot is empty
+pub fn read_slot_metadata(slot: &str) -> Result<Option<SaveMetadata>, SaveError> {
+    let path: PathBuf = slot_path(slot)?;
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents: String = fs::read_to_string(&path)?;
+    check_version(&contents)?;
+    let summary: SaveSummary = ron::from_str(&contents)?;
+
+    return Ok(Some(summary.metadata));
+}
+
+pub fn delete_slot(slot: &str) -> Result<(), SaveError> {
+    let path: PathBuf = slot_path(slot)?;
+
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+
+    return Ok(());
+}
+
+pub fn rename_slot(slot: &str, name: &str) -> Result<(), SaveError> {
+    let path: PathBuf = slot_path(slot)?;
+    let mut save: SaveGame = read_save(&path)?;
+    save.metadata.name = name.to_string();
+
+    return write_save(&path, &save);
+}
+
+pub type PlayerSaveQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static Velocity,
+        &'static AngularVelocity,
+        &'static InertialDampener,
+        &'static Wallet,
+        &'static Cargo,
+        Option<&'static Docked>,
+    ),
+    With<Player>,
+>;
+
+pub type EnemySaveQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static Velocity,
+        &'static Acceleration,
+        &'static Enemy,
+    ),
+>;
+
+// Request to load a save slot and start a session from it
+#[derive(Event, Debug)]
+pub struct OnLoadGame {
+    pub slot: String,
+}
+
+#[derive(Event, Debug)]
+pub struct OnLoadFailed {
+    pub message: String,
+}
+
+// Sent after a slot is written, deleted or renamed so lists of slots can refresh
+#[derive(Event, Debug)]
+pub struct OnSlotsChanged;
+
+#[derive(Resource, Debug)]
+struct AutosaveTimer(Timer);
+
+// Save waiting to be applied once the session's entities have been spawned
+#[derive(Resource, Debug)]
+pub struct PendingLoad(SaveGame);
+
+pub struct SaveGamePlugin;
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(AutosaveTimer(Timer::from_seconds(
+            AUTOSAVE_INTERVAL_SECONDS,
+            TimerMode::Repeating,
+        )))
+        .add_event::<OnLoadGame>()
+        .add_event::<OnLoadFailed>()
+        .add_event::<OnSlotsChanged>()
+        .add_systems(
+            Update,
+            (
+                // Replays shouldn't overwrite the player's own autosave
+                autosave
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(not(resource_exists::<InputReplay>())),
+                save_game.run_if(on_event::<OnSaveGame>()),
+            )
+                .chain(),
+        )
+        .add_systems(Update, load_game.run_if(on_event::<OnLoadGame>()))
+        .add_systems(
+            OnEnter(GameState::LoadingGame),
+            (
+                reset_autosave_timer.in_set(SessionSetupSet),
+                // The player and stations are spawned through commands, so they only exist once
+                // those have been applied
+                apply_deferred
+                    .after(SessionSetupSet)
+                    .before(restore_pending_load),
+                restore_pending_load.after(SessionSetupSet),
+            ),
+        );
+    }
+}
+
+pub fn create_save(
+    name: String,
+    player_query: &PlayerSaveQuery,
+    station_query: &Query<(&Station, &Transform)>,
+    enemy_query: &EnemySaveQuery,
+    clock: &GameClock,
+) -> Option<SaveGame> {
+    let Ok((transform, velocity, angular_velocity, dampener, wallet, cargo, docked)) =
+        player_query.get_single()
+    else {
+        return None;
+    };
+
+    let timestamp: u64 = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    let stations: Vec<StationSave> = station_query
+        .iter()
+        .map(|(station, _)| StationSave {
+            name: station.name.clone(),
+            inventory: station.inventory.clone(),
+        })
+        .collect();
+
+    let enemies: Vec<EnemySave> = enemy_query
+        .iter()
+        .map(|(transform, velocity, acceleration, enemy)| EnemySave {
+            transform: *transform,
+            velocity: velocity.value,
+            acceleration: acceleration.value,
+            despawn_timer: enemy.despawn_timer.clone(),
+        })
+        .collect();
+
+    return Some(SaveGame {
+        version: SAVE_VERSION,
+        metadata: SaveMetadata {
+            name,
+            timestamp,
+            location: describe_location(transform.translation, docked, station_query),
+            credits: wallet.credits,
+            playtime_seconds: clock.elapsed_seconds,
+        },
+        player: PlayerSave {
+            transform: *transform,
+            velocity: velocity.value,
+            angular_velocity: angular_velocity.value,
+            inertial_dampener: dampener.enabled,
+            credits: wallet.credits,
+            cargo: cargo.inventory.clone(),
+            docked_station: docked
+                .and_then(|docked| station_query.get(docked.station).ok())
+                .map(|(station, _)| station.name.clone()),
+        },
+        stations,
+        enemies,
+        clock_seconds: clock.elapsed_seconds,
+    });
+}
+
+fn describe_location(
+    position: Vec3,
+    docked: Option<&Docked>,
+    station_query: &Query<(&Station, &Transform)>,
+) -> String {
+    if let Some((station, _)) = docked.and_then(|docked| station_query.get(docked.station).ok()) {
+        return format!("Docked at {}", station.name);
+    }
+
+    let nearest = station_query
+        .iter()
+        .map(|(station, transform)| (station, transform.translation.distance(position)))
+        .filter(|(_, distance)| *distance <= LOCATION_RANGE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    return match nearest {
+        Some((station, _)) => format!("Near {}", station.name),
+        None => "Deep Space".to_string(),
+    };
+}
+
+fn reset_autosave_timer(mut timer: ResMut<AutosaveTimer>) {
+    timer.0.reset();
+}
+
+fn autosave(
+    mut timer: ResMut<AutosaveTimer>,
+    mut dock_reader: EventReader<OnDocked>,
+    mut event_writer: EventWriter<OnSaveGame>,
+    time: Res<Time>,
+) {
+    let docked: bool = dock_reader.read().count() > 0;
+
+    if timer.0.tick(time.delta()).just_finished() || docked {
+        event_writer.send(OnSaveGame {
+            slot: AUTOSAVE_SLOT.to_string(),
+        });
+    }
+}
+
+fn save_game(
+    mut event_reader: EventReader<OnSaveGame>,
+    mut changed_writer: EventWriter<OnSlotsChanged>,
+    player_query: PlayerSaveQuery,
+    station_query: Query<(&Station, &Transform)>,
+    enemy_query: EnemySaveQuery,
+    clock: Res<GameClock>,
+) {
+    let mut slots: Vec<String> = event_reader
+        .read()
+        .map(|event| event.slot.clone())
+        .collect();
+    // Several requests for a slot in one frame only need one save
+    slots.sort();
+    slots.dedup();
+
+    for slot in slots {
+        // Overwriting a slot keeps the name the player gave it
+        let name: String = read_slot_metadata(&slot)
+            .ok()
+            .flatten()
+            .map(|metadata| metadata.name)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| default_slot_name(&slot));
+
+        let Some(save) = create_save(name, &player_query, &station_query, &enemy_query, &clock)
+        else {
+            warn!("Nothing to save to {}, no player in the world", slot);
+            continue;
+        };
+
+        match slot_path(&slot).and_then(|path| write_save(&path, &save)) {
+            Ok(()) => info!("Game saved to {}", slot),
+            Err(error) => error!("Failed to save game to {}, {}", slot, error),
+        }
+    }
+
+    changed_writer.send(OnSlotsChanged);
+}
+
+fn load_game(
+    mut commands: Commands,
+    mut event_reader: EventReader<OnLoadGame>,
+    mut start_writer: EventWriter<OnGameStart>,
+    mut failed_writer: EventWriter<OnLoadFailed>,
+) {
+    // Only the last request matters if several were sent
+    let Some(slot) = event_reader.read().last().map(|event| event.slot.clone()) else {
+        return;
+    };
+
+    match slot_path(&slot).and_then(|path| read_save(&path)) {
+        Ok(save) => {
+            commands.insert_resource(PendingLoad(save));
+            start_writer.send(OnGameStart);
+        }
+        Err(error) => {
+            error!("Failed to load game from {}, {}", slot, error);
+            failed_writer.send(OnLoadFailed {
+                message: error.to_string(),
+            });
+        }
+    }
+}
+
+fn restore_pending_load(
+    mut commands: Commands,
+    pending: Option<Res<PendingLoad>>,
+    image_assets: Res<ImageAssets>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut AngularVelocity,
+            &mut InertialDampener,
+            &mut Wallet,
+            &mut Cargo,
+        ),
+        With<Player>,
+    >,
+    mut station_query: Query<(Entity, &mut Station)>,
+    mut clock: ResMut<GameClock>,
+) {
+    let Some(pending) = pending else {
+        return;
+    };
+
+    commands.remove_resource::<PendingLoad>();
+
+    let save: &SaveGame = &pending.0;
+
+    if let Ok((
+        entity,
+        mut transform,
+        mut velocity,
+        mut angular_velocity,
+        mut dampener,
+        mut wallet,
+        mut cargo,
+    )) = player_query.get_single_mut()
+    {
+        *transform = save.player.transform;
+        velocity.value = save.player.velocity;
+        angular_velocity.value = save.player.angular_velocity;
+        dampener.enabled = save.player.inertial_dampener;
+        wallet.credits = save.player.credits;
+        cargo.inventory = save.player.cargo.clone();
+
+        // Saves made while docked restore docked, at the station with the same name
+        let docked_station: Option<Entity> = station_query
+            .iter()
+            .find(|(_, station)| save.player.docked_station.as_ref() == Some(&station.name))
+            .map(|(station, _)| station);
+
+        if let Some(station) = docked_station {
+            dock_entity(&mut commands, entity, station);
+        }
+    }
+
+    // Stations are spawned by the level, so match them up by name
+    for (_, mut station) in station_query.iter_mut() {
+        let Some(saved) = save
+            .stations
+            .iter()
+            .find(|saved| saved.name == station.name)
+        else {
+            continue;
+        };
+
+        station.inventory = saved.inventory.clone();
+    }
+
+    for enemy in save.enemies.iter() {
+        spawn_enemy_entity(
+            &mut commands,
+            &image_assets,
+            enemy.transform.translation,
+            enemy.velocity,
+            enemy.acceleration,
+            enemy.despawn_timer.clone(),
+        );
+    }
+
+    clock.elapsed_seconds = save.clock_seconds;
+
+    info!("Restored saved game");
+}
@@ -1,6 +1,11 @@
-use crate::state::GameState;
+use crate::state::{GameState, SessionSetupSet};
 use bevy::prelude::*;
+use rand::prelude::*;
 
+// Simulation ticks per second, every `InGameSet` runs once per tick
+pub const SIMULATION_HZ: f64 = 60.0;
+
+// Simulation sets, run in `FixedUpdate`
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum InGameSet {
     UserInput,
@@ -9,27 +14,141 @@ pub enum InGameSet {
     DespawnEntities,
 }
 
+// Presentation sets, run in `Update` after the simulation has stepped
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub enum RenderSet {
+    Interpolation,
+    CameraUpdates,
+    CameraEffects,
+}
+
+// Random number generator for anything that affects the simulation,
+// seeded so the same inputs always produce the same world state
+#[derive(Resource, Debug)]
+pub struct SimulationRng(pub StdRng);
+
+impl SimulationRng {
+    pub fn new(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+// Seed the current session's `SimulationRng` started from
+#[derive(Resource, Debug, Default)]
+pub struct SimulationSeed(pub u64);
+
+// Starts sessions from this seed instead of a fresh one, so tests and replays can reproduce a
+// session exactly
+#[derive(Resource, Debug)]
+pub struct SimulationSeedOverride(pub u64);
+
+// Simulated time since the session started, advances one fixed step per tick
+#[derive(Resource, Debug, Default)]
+pub struct GameClock {
+    pub elapsed_seconds: f64,
+}
+
 pub struct SchedulePlugin;
 
 impl Plugin for SchedulePlugin {
     fn build(&self, app: &mut App) {
-        app.configure_sets(
-            Update,
-            (
-                InGameSet::DespawnEntities,
-                // Flush commands (i.e. `apply_deferred` runs)
-                InGameSet::UserInput,
-                InGameSet::EntityUpdates,
-                InGameSet::CollisionDetection,
+        app.insert_resource(Time::<Fixed>::from_hz(SIMULATION_HZ))
+            .insert_resource(SimulationRng::new(random()))
+            .init_resource::<SimulationSeed>()
+            .init_resource::<GameClock>()
+            .configure_sets(
+                FixedUpdate,
+                (
+                    InGameSet::DespawnEntities,
+                    // Flush commands (i.e. `apply_deferred` runs)
+                    InGameSet::UserInput,
+                    InGameSet::EntityUpdates,
+                    InGameSet::CollisionDetection,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                apply_deferred
+                    .after(InGameSet::DespawnEntities)
+                    .before(InGameSet::UserInput),
             )
-                .chain()
-                .run_if(in_state(GameState::InGame)),
-        )
-        .add_systems(
-            Update,
-            apply_deferred
-                .after(InGameSet::DespawnEntities)
-                .before(InGameSet::UserInput),
-        );
+            .configure_sets(
+                Update,
+                (
+                    RenderSet::Interpolation.run_if(in_state(GameState::InGame)),
+                    RenderSet::CameraUpdates,
+                    RenderSet::CameraEffects,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                FixedUpdate,
+                tick_game_clock.in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(
+                OnEnter(GameState::LoadingGame),
+                (reset_simulation_rng, reset_game_clock).in_set(SessionSetupSet),
+            );
+    }
+}
+
+fn reset_simulation_rng(
+    mut rng: ResMut<SimulationRng>,
+    mut seed: ResMut<SimulationSeed>,
+    seed_override: Option<Res<SimulationSeedOverride>>,
+) {
+    // Every session plays out differently unless it's been told which seed to use
+    seed.0 = seed_override.map_or_else(random, |seed_override| seed_override.0);
+    *rng = SimulationRng::new(seed.0);
+}
+
+fn reset_game_clock(mut clock: ResMut<GameClock>) {
+    *clock = GameClock::default();
+}
+
+fn tick_game_clock(mut clock: ResMut<GameClock>, time: Res<Time>) {
+    clock.elapsed_seconds += time.delta_seconds_f64();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enemy::Enemy;
+    use crate::player::Player;
+    use crate::testing::{run_ticks, simulation_app, start_session};
+
+    const SEED: u64 = 0x5eed_cafe;
+    const TICKS: u32 = 60 * 10;
+
+    // Runs a session for a number of ticks, returning where the player and every enemy ended up
+    fn simulate(seed: u64, ticks: u32) -> Vec<Transform> {
+        let mut app: App = simulation_app(seed);
+        start_session(&mut app);
+        run_ticks(&mut app, ticks);
+
+        let mut query: QueryState<&Transform, Or<(With<Player>, With<Enemy>)>> =
+            app.world.query_filtered();
+
+        return query.iter(&app.world).copied().collect();
+    }
+
+    #[test]
+    fn same_seed_gives_identical_transforms() {
+        let first: Vec<Transform> = simulate(SEED, TICKS);
+        let second: Vec<Transform> = simulate(SEED, TICKS);
+
+        // the player plus at least one enemy
+        assert!(first.len() > 1, "no enemies spawned");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn different_seed_gives_different_transforms() {
+        let first: Vec<Transform> = simulate(SEED, TICKS);
+        let second: Vec<Transform> = simulate(SEED + 1, TICKS);
+
+        assert_ne!(first, second);
     }
 }
@@ -0,0 +1,334 @@
+use crate::camera_effects::CameraEffectSettings;
+use crate::input::InputBindings;
+use crate::util::config_directory;
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const SETTINGS_FILE: &str = "settings.ron";
+const VOLUME_STEP: f32 = 0.1;
+const DEADZONE_STEP: f32 = 0.05;
+const MAX_DEADZONE: f32 = 0.3;
+const RESOLUTIONS: [(u32, u32); 4] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowModeSetting {
+    Windowed,
+    BorderlessFullscreen,
+    Fullscreen,
+}
+
+impl From<WindowModeSetting> for WindowMode {
+    fn from(mode: WindowModeSetting) -> Self {
+        match mode {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::BorderlessFullscreen => WindowMode::BorderlessFullscreen,
+            WindowModeSetting::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // Multiplier for the time between enemy spawns
+    pub fn spawn_interval_scale(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ControlScheme {
+    // turn with the keys and thrust along the ship's facing
+    #[default]
+    Tank,
+    // face the mouse cursor and thrust with the keys
+    MouseAim,
+}
+
+// The directions mouse aim thrust moves in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThrustSpace {
+    // forward, back and strafe relative to where the ship is facing
+    #[default]
+    Ship,
+    // up, down, left and right on screen
+    World,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    // 0.0 to 1.0
+    pub master_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { master_volume: 0.8 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub window_mode: WindowModeSetting,
+    pub vsync: bool,
+    pub resolution: (u32, u32),
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowModeSetting::Windowed,
+            vsync: true,
+            resolution: RESOLUTIONS[0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    pub difficulty: Difficulty,
+    pub screen_shake: bool,
+    pub control_scheme: ControlScheme,
+    pub thrust_space: ThrustSpace,
+    // fraction of the gamepad stick's travel that's ignored
+    pub stick_deadzone: f32,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            difficulty: Difficulty::Normal,
+            screen_shake: true,
+            control_scheme: ControlScheme::Tank,
+            thrust_space: ThrustSpace::Ship,
+            stick_deadzone: 0.15,
+        }
+    }
+}
+
+// Everything the player can change from the settings screen, saved to the config file.
+// Missing fields in the file fall back to their defaults so older files still load.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    pub audio: AudioSettings,
+    pub display: DisplaySettings,
+    pub gameplay: GameplaySettings,
+    pub controls: InputBindings,
+}
+
+// A single option shown on the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    MasterVolume,
+    WindowMode,
+    Vsync,
+    Resolution,
+    Difficulty,
+    ScreenShake,
+    ControlScheme,
+    ThrustSpace,
+    StickDeadzone,
+}
+
+impl Setting {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::MasterVolume => "Master Volume",
+            Setting::WindowMode => "Window Mode",
+            Setting::Vsync => "VSync",
+            Setting::Resolution => "Resolution",
+            Setting::Difficulty => "Difficulty (next game)",
+            Setting::ScreenShake => "Screen Shake",
+            Setting::ControlScheme => "Control Scheme",
+            Setting::ThrustSpace => "Mouse Aim Thrust",
+            Setting::StickDeadzone => "Stick Deadzone",
+        }
+    }
+}
+
+impl GameSettings {
+    // Steps the setting on to its next value, wrapping back round to the first
+    pub fn cycle(&mut self, setting: Setting) {
+        match setting {
+            Setting::MasterVolume => {
+                let volume: f32 = self.audio.master_volume + VOLUME_STEP;
+                // Small tolerance so float error doesn't skip 100%
+                self.audio.master_volume = if volume > 1.0 + VOLUME_STEP / 2.0 {
+                    0.0
+                } else {
+                    volume.min(1.0)
+                };
+            }
+            Setting::WindowMode => {
+                self.display.window_mode = match self.display.window_mode {
+                    WindowModeSetting::Windowed => WindowModeSetting::BorderlessFullscreen,
+                    WindowModeSetting::BorderlessFullscreen => WindowModeSetting::Fullscreen,
+                    WindowModeSetting::Fullscreen => WindowModeSetting::Windowed,
+                };
+            }
+            Setting::Vsync => self.display.vsync = !self.display.vsync,
+            Setting::Resolution => {
+                let next: usize = RESOLUTIONS
+                    .iter()
+                    .position(|resolution| *resolution == self.display.resolution)
+                    .map(|index| (index + 1) % RESOLUTIONS.len())
+                    .unwrap_or(0);
+                self.display.resolution = RESOLUTIONS[next];
+            }
+            Setting::Difficulty => {
+                self.gameplay.difficulty = match self.gameplay.difficulty {
+                    Difficulty::Easy => Difficulty::Normal,
+                    Difficulty::Normal => Difficulty::Hard,
+                    Difficulty::Hard => Difficulty::Easy,
+                };
+            }
+            Setting::ScreenShake => self.gameplay.screen_shake = !self.gameplay.screen_shake,
+            Setting::ControlScheme => {
+                self.gameplay.control_scheme = match self.gameplay.control_scheme {
+                    ControlScheme::Tank => ControlScheme::MouseAim,
+                    ControlScheme::MouseAim => ControlScheme::Tank,
+                };
+            }
+            Setting::ThrustSpace => {
+                self.gameplay.thrust_space = match self.gameplay.thrust_space {
+                    ThrustSpace::Ship => ThrustSpace::World,
+                    ThrustSpace::World => ThrustSpace::Ship,
+                };
+            }
+            Setting::StickDeadzone => {
+                let deadzone: f32 = self.gameplay.stick_deadzone + DEADZONE_STEP;
+                self.gameplay.stick_deadzone = if deadzone > MAX_DEADZONE + DEADZONE_STEP / 2.0 {
+                    DEADZONE_STEP
+                } else {
+                    deadzone.min(MAX_DEADZONE)
+                };
+            }
+        }
+    }
+
+    pub fn value_label(&self, setting: Setting) -> String {
+        return match setting {
+            Setting::MasterVolume => format!("{:.0}%", self.audio.master_volume * 100.0),
+            Setting::WindowMode => match self.display.window_mode {
+                WindowModeSetting::Windowed => "Windowed".to_string(),
+                WindowModeSetting::BorderlessFullscreen => "Borderless".to_string(),
+                WindowModeSetting::Fullscreen => "Fullscreen".to_string(),
+            },
+            Setting::Vsync => on_off(self.display.vsync),
+            Setting::Resolution => format!(
+                "{}x{}",
+                self.display.resolution.0, self.display.resolution.1
+            ),
+            Setting::Difficulty => format!("{:?}", self.gameplay.difficulty),
+            Setting::ScreenShake => on_off(self.gameplay.screen_shake),
+            Setting::ControlScheme => match self.gameplay.control_scheme {
+                ControlScheme::Tank => "Tank".to_string(),
+                ControlScheme::MouseAim => "Mouse Aim".to_string(),
+            },
+            Setting::ThrustSpace => match self.gameplay.thrust_space {
+                ThrustSpace::Ship => "Ship Relative".to_string(),
+                ThrustSpace::World => "World Relative".to_string(),
+            },
+            Setting::StickDeadzone => format!("{:.0}%", self.gameplay.stick_deadzone * 100.0),
+        };
+    }
+
+    pub fn load() -> Result<Self, String> {
+        let path: PathBuf = settings_path().ok_or("no config directory")?;
+        let contents: String = fs::read_to_string(&path)
+            .map_err(|error| format!("couldn't read {}: {}", path.display(), error))?;
+
+        return ron::from_str(&contents)
+            .map_err(|error| format!("couldn't parse {}: {}", path.display(), error));
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path: PathBuf = settings_path().ok_or("no config directory")?;
+
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)
+                .map_err(|error| format!("couldn't create {}: {}", directory.display(), error))?;
+        }
+
+        let contents: String = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|error| format!("couldn't serialise settings: {}", error))?;
+
+        return fs::write(&path, contents)
+            .map_err(|error| format!("couldn't write {}: {}", path.display(), error));
+    }
+}
+
+fn on_off(value: bool) -> String {
+    return if value { "On" } else { "Off" }.to_string();
+}
+
+fn settings_path() -> Option<PathBuf> {
+    return config_directory().map(|directory| directory.join(SETTINGS_FILE));
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameSettings>()
+            .add_systems(Startup, load_settings)
+            .add_systems(
+                Update,
+                apply_settings.run_if(resource_changed::<GameSettings>()),
+            );
+    }
+}
+
+fn load_settings(mut settings: ResMut<GameSettings>) {
+    match GameSettings::load() {
+        Ok(loaded) => *settings = loaded,
+        // Missing on first run, so just keep the defaults
+        Err(error) => info!("Using default settings, {}", error),
+    }
+}
+
+pub fn save_settings(settings: Res<GameSettings>) {
+    if let Err(error) = settings.save() {
+        warn!("Failed to save settings, {}", error);
+    }
+}
+
+fn apply_settings(
+    settings: Res<GameSettings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut global_volume: ResMut<GlobalVolume>,
+    mut camera_effect_settings: ResMut<CameraEffectSettings>,
+) {
+    *global_volume = GlobalVolume::new(settings.audio.master_volume);
+    camera_effect_settings.shake_enabled = settings.gameplay.screen_shake;
+
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    let (width, height) = settings.display.resolution;
+
+    window.mode = settings.display.window_mode.into();
+    window.present_mode = if settings.display.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    window.resolution.set(width as f32, height as f32);
+}
@@ -0,0 +1,229 @@
+use crate::camera::MainCamera;
+use crate::schedule::RenderSet;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use rand::prelude::*;
+
+const STARFIELD_SEED: u64 = 0x57a2_f1e1;
+// Size of the square tiles each layer is generated in, in layer space
+const TILE_SIZE: f32 = 512.0;
+// Layers are drawn from this z upwards, behind the planets at 0.5. The 2D camera only sees z
+// from just below 0.0 up to its own position, so anything further back is culled.
+const STARFIELD_Z: f32 = 0.0;
+const STARFIELD_LAYER_STEP: f32 = 0.1;
+
+#[derive(Debug, Clone)]
+pub struct StarLayer {
+    // how much of the camera's movement the layer follows, 1.0 moves with the world,
+    // lower values look further away
+    pub parallax: f32,
+    pub stars_per_tile: usize,
+    pub star_size: f32,
+    pub brightness: f32,
+}
+
+#[derive(Resource, Debug)]
+pub struct StarfieldSettings {
+    // same seed always generates the same sky
+    pub seed: u64,
+    // furthest layer first
+    pub layers: Vec<StarLayer>,
+}
+
+impl Default for StarfieldSettings {
+    fn default() -> Self {
+        Self {
+            seed: STARFIELD_SEED,
+            layers: vec![
+                StarLayer {
+                    parallax: 0.1,
+                    stars_per_tile: 24,
+                    star_size: 1.0,
+                    brightness: 0.35,
+                },
+                StarLayer {
+                    parallax: 0.3,
+                    stars_per_tile: 12,
+                    star_size: 1.5,
+                    brightness: 0.6,
+                },
+                StarLayer {
+                    parallax: 0.6,
+                    stars_per_tile: 6,
+                    star_size: 2.0,
+                    brightness: 0.9,
+                },
+            ],
+        }
+    }
+}
+
+// Parent of every star in a layer, moved with the camera to give the parallax
+#[derive(Component, Debug)]
+struct StarfieldLayer {
+    index: usize,
+    // stars in each visible tile
+    tiles: HashMap<IVec2, Vec<Entity>>,
+    // hidden stars from tiles that scrolled out of view, reused for new tiles
+    free: Vec<Entity>,
+}
+
+#[derive(Component, Debug)]
+struct Star;
+
+pub struct StarfieldPlugin;
+
+impl Plugin for StarfieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StarfieldSettings>()
+            .add_systems(Startup, spawn_starfield)
+            .add_systems(Update, update_starfield.after(RenderSet::CameraEffects));
+    }
+}
+
+fn spawn_starfield(mut commands: Commands, settings: Res<StarfieldSettings>) {
+    for index in 0..settings.layers.len() {
+        commands.spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(
+                0.0,
+                0.0,
+                STARFIELD_Z + index as f32 * STARFIELD_LAYER_STEP,
+            )),
+            StarfieldLayer {
+                index,
+                tiles: HashMap::new(),
+                free: Vec::new(),
+            },
+        ));
+    }
+}
+
+fn update_starfield(
+    mut commands: Commands,
+    settings: Res<StarfieldSettings>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    mut layer_query: Query<
+        (Entity, &mut StarfieldLayer, &mut Transform),
+        (Without<MainCamera>, Without<Star>),
+    >,
+    mut star_query: Query<
+        (&mut Transform, &mut Sprite, &mut Visibility),
+        (With<Star>, Without<StarfieldLayer>, Without<MainCamera>),
+    >,
+) {
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+
+    let camera_position: Vec2 = camera_transform.translation.truncate();
+    // Extra half tile around the view covers the screen shake's rotation
+    let half_extent: Vec2 = projection.area.half_size() + Vec2::splat(TILE_SIZE / 2.0);
+
+    for (layer_entity, mut layer, mut layer_transform) in layer_query.iter_mut() {
+        let Some(settings_layer) = settings.layers.get(layer.index) else {
+            continue;
+        };
+
+        // Shift the layer so its stars only move by `parallax` of the camera's movement
+        layer_transform.translation = (camera_position * (1.0 - settings_layer.parallax))
+            .extend(layer_transform.translation.z);
+
+        let layer_camera: Vec2 = camera_position * settings_layer.parallax;
+        let min: IVec2 = ((layer_camera - half_extent) / TILE_SIZE)
+            .floor()
+            .as_ivec2();
+        let max: IVec2 = ((layer_camera + half_extent) / TILE_SIZE)
+            .floor()
+            .as_ivec2();
+        let in_view = |coord: &IVec2| coord.cmpge(min).all() && coord.cmple(max).all();
+
+        let layer: &mut StarfieldLayer = &mut layer;
+
+        // Hide and keep the stars of tiles that are no longer in view
+        layer.tiles.retain(|coord, stars| {
+            if in_view(coord) {
+                return true;
+            }
+
+            for star in stars.drain(..) {
+                if let Ok((_, _, mut visibility)) = star_query.get_mut(star) {
+                    *visibility = Visibility::Hidden;
+                }
+
+                layer.free.push(star);
+            }
+
+            return false;
+        });
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let coord: IVec2 = IVec2::new(x, y);
+
+                if layer.tiles.contains_key(&coord) {
+                    continue;
+                }
+
+                let mut rng: StdRng =
+                    StdRng::seed_from_u64(tile_seed(settings.seed, layer.index, coord));
+                let mut stars: Vec<Entity> = Vec::with_capacity(settings_layer.stars_per_tile);
+
+                for _ in 0..settings_layer.stars_per_tile {
+                    let position: Vec2 =
+                        (coord.as_vec2() + Vec2::new(rng.gen(), rng.gen())) * TILE_SIZE;
+                    let brightness: f32 = settings_layer.brightness * rng.gen_range(0.5..=1.0);
+                    let size: f32 = settings_layer.star_size * rng.gen_range(0.75..=1.25);
+                    let colour: Color = Color::rgb(brightness, brightness, brightness);
+
+                    let recycled: Option<Entity> = layer.free.pop();
+
+                    if let Some(star) = recycled {
+                        if let Ok((mut transform, mut sprite, mut visibility)) =
+                            star_query.get_mut(star)
+                        {
+                            transform.translation = position.extend(0.0);
+                            sprite.color = colour;
+                            sprite.custom_size = Some(Vec2::splat(size));
+                            *visibility = Visibility::Inherited;
+                        }
+
+                        stars.push(star);
+                        continue;
+                    }
+
+                    let star: Entity = commands
+                        .spawn((
+                            SpriteBundle {
+                                sprite: Sprite {
+                                    color: colour,
+                                    custom_size: Some(Vec2::splat(size)),
+                                    ..default()
+                                },
+                                transform: Transform::from_translation(position.extend(0.0)),
+                                ..default()
+                            },
+                            Star,
+                        ))
+                        .id();
+                    commands.entity(layer_entity).add_child(star);
+                    stars.push(star);
+                }
+
+                layer.tiles.insert(coord, stars);
+            }
+        }
+    }
+}
+
+// Mixes the seed, layer and tile into a seed for the tile's stars, so any tile can be
+// regenerated on its own
+fn tile_seed(seed: u64, layer: usize, coord: IVec2) -> u64 {
+    let mut hash: u64 = seed ^ (layer as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    hash ^= ((coord.x as u32 as u64) << 32) | coord.y as u32 as u64;
+
+    // splitmix64 finaliser
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+    return hash ^ (hash >> 31);
+}
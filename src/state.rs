@@ -1,3 +1,5 @@
+use crate::input::{ActionState, InputAction};
+use crate::util::despawn_components;
 use bevy::prelude::*;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
@@ -5,32 +7,54 @@ pub enum GameState {
     #[default]
     StartMenu,
     LoadingGame,
+    LoadingFailed,
     InGame,
     InGameMenu,
     Paused,
+    Settings,
+    SaveSlots,
 }
 
 #[derive(Event, Debug)]
 pub struct OnGameStart;
 
+// Marks entities that belong to a single play session. A session starts on entering LoadingGame
+// from the start menu, and everything tagged is despawned on returning to the start menu.
+#[derive(Component, Debug)]
+pub struct SessionScoped;
+
+// Systems that spawn a session's entities and reset its resources on entering LoadingGame
+#[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
+pub struct SessionSetupSet;
+
+// Request to save the current game to a save slot
+#[derive(Event, Debug)]
+pub struct OnSaveGame {
+    pub slot: String,
+}
+
 pub struct StatePlugin;
 
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<GameState>()
             .add_event::<OnGameStart>()
+            .add_event::<OnSaveGame>()
             .add_systems(Update, on_game_start.run_if(on_event::<OnGameStart>()))
             .add_systems(Update, game_state_input_events)
-            .add_systems(OnEnter(GameState::LoadingGame), on_enter_loading_game);
+            .add_systems(
+                OnEnter(GameState::StartMenu),
+                despawn_components::<SessionScoped>,
+            );
     }
 }
 
 fn game_state_input_events(
     mut next_state: ResMut<NextState<GameState>>,
     state: Res<State<GameState>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<ActionState>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if actions.just_pressed(InputAction::Pause) {
         match state.get() {
             GameState::InGame => next_state.set(GameState::Paused),
             GameState::Paused => next_state.set(GameState::InGame),
@@ -44,9 +68,3 @@ fn on_game_start(mut next_state: ResMut<NextState<GameState>>) {
     // to allow game setup
     next_state.set(GameState::LoadingGame);
 }
-
-fn on_enter_loading_game(mut next_state: ResMut<NextState<GameState>>) {
-    // After entering LoadingGame state, queue up change to InGame.
-    // Is allows assets and setup to happen before game starts.
-    next_state.set(GameState::InGame);
-}
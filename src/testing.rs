@@ -0,0 +1,48 @@
+use crate::asset_loader::ImageAssets;
+use crate::enemy::EnemyPlugin;
+use crate::movement::MovementPlugin;
+use crate::player::PlayerPlugin;
+use crate::projectile::ProjectilePlugin;
+use crate::schedule::{SchedulePlugin, SimulationSeedOverride, SIMULATION_HZ};
+use crate::settings::GameSettings;
+use crate::state::GameState;
+use crate::world_bounds::{OnLeftWorldBounds, WorldBounds};
+use bevy::prelude::*;
+use std::time::Duration;
+
+// The simulation without rendering, windows or input devices, stepped by hand in tests
+pub fn simulation_app(seed: u64) -> App {
+    let mut app: App = App::new();
+    app.add_state::<GameState>()
+        .insert_resource(SimulationSeedOverride(seed))
+        .init_resource::<GameSettings>()
+        .init_resource::<ImageAssets>()
+        .init_resource::<WorldBounds>()
+        .add_event::<OnLeftWorldBounds>()
+        .add_plugins((
+            SchedulePlugin,
+            MovementPlugin,
+            ProjectilePlugin,
+            PlayerPlugin,
+            EnemyPlugin,
+        ));
+
+    // Every tick sees exactly one fixed step, with no real clock involved
+    let mut time: Time = Time::default();
+    time.advance_by(Duration::from_secs_f64(1.0 / SIMULATION_HZ));
+    app.insert_resource(time);
+
+    return app;
+}
+
+// Sets up a new session, as if it had been started from the start menu
+pub fn start_session(app: &mut App) {
+    app.world.run_schedule(OnEnter(GameState::LoadingGame));
+    app.insert_resource(State::new(GameState::InGame));
+}
+
+pub fn run_ticks(app: &mut App, ticks: u32) {
+    for _ in 0..ticks {
+        app.world.run_schedule(FixedUpdate);
+    }
+}
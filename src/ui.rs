@@ -1,6 +1,20 @@
+mod loading_screen;
+mod menu_navigation;
+mod minimap;
+mod offscreen_indicators;
+mod pause_menu;
+mod save_slots_menu;
+mod settings_menu;
 mod station_menu;
 
 use crate::state::{GameState, OnGameStart};
+use crate::ui::loading_screen::LoadingScreenPlugin;
+use crate::ui::menu_navigation::MenuNavigationPlugin;
+use crate::ui::minimap::MinimapPlugin;
+use crate::ui::offscreen_indicators::OffscreenIndicatorPlugin;
+use crate::ui::pause_menu::PauseMenuPlugin;
+use crate::ui::save_slots_menu::{SaveSlotsMenu, SaveSlotsMenuPlugin, SlotMenuMode};
+use crate::ui::settings_menu::{SettingsMenuPlugin, SettingsReturnState};
 use crate::ui::station_menu::StationMenuPlugin;
 use crate::util::despawn_components;
 use bevy::{app::AppExit, prelude::*};
@@ -13,6 +27,9 @@ pub struct StartUIRoot;
 #[derive(Component, Debug)]
 pub struct StartButton;
 
+#[derive(Component, Debug)]
+pub struct LoadButton;
+
 #[derive(Component, Debug)]
 pub struct SettingsButton;
 
@@ -22,10 +39,33 @@ pub struct ExitButton;
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(StationMenuPlugin)
+            .add_plugins(MinimapPlugin)
+            .add_plugins(OffscreenIndicatorPlugin)
+            .add_plugins(SettingsMenuPlugin)
+            .add_plugins(PauseMenuPlugin)
+            .add_plugins(LoadingScreenPlugin)
+            .add_plugins(SaveSlotsMenuPlugin)
+            .add_plugins(MenuNavigationPlugin)
             .add_systems(OnEnter(GameState::StartMenu), spawn_start_menu)
             .add_systems(
                 Update,
-                (button_system, on_click_start, on_click_exit)
+                button_system.run_if(
+                    in_state(GameState::StartMenu)
+                        .or_else(in_state(GameState::Settings))
+                        .or_else(in_state(GameState::Paused))
+                        .or_else(in_state(GameState::InGameMenu))
+                        .or_else(in_state(GameState::LoadingFailed))
+                        .or_else(in_state(GameState::SaveSlots)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    on_click_start,
+                    on_click_load,
+                    on_click_settings,
+                    on_click_exit,
+                )
                     .chain()
                     .run_if(in_state(GameState::StartMenu)),
             )
@@ -156,6 +196,14 @@ fn spawn_start_menu(mut commands: Commands) {
     commands.entity(button).push_children(&[button_text]);
     commands.entity(container).push_children(&[button]);
 
+    // Create and spawn Load Game Button
+    let button = commands.spawn((get_button_bundle(), LoadButton)).id();
+    let button_text = commands
+        .spawn(get_text_bundle("Load Game".to_string(), 40.0))
+        .id();
+    commands.entity(button).push_children(&[button_text]);
+    commands.entity(container).push_children(&[button]);
+
     // Create and spawn Settings Button
     let button = commands.spawn((get_button_bundle(), SettingsButton)).id();
     let button_text = commands
@@ -214,6 +262,41 @@ fn on_click_start(
     }
 }
 
+fn on_click_load(
+    mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<LoadButton>)>,
+    mut slots_menu: ResMut<SaveSlotsMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *slots_menu = SaveSlotsMenu {
+                    mode: SlotMenuMode::Load,
+                    return_to: GameState::StartMenu,
+                };
+                next_state.set(GameState::SaveSlots);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn on_click_settings(
+    mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<SettingsButton>)>,
+    mut return_state: ResMut<SettingsReturnState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                return_state.0 = GameState::StartMenu;
+                next_state.set(GameState::Settings);
+            }
+            _ => {}
+        }
+    }
+}
+
 fn on_click_exit(
     mut app_exit_events: EventWriter<AppExit>,
     mut interaction_query: Query<&Interaction, (Changed<Interaction>, With<ExitButton>)>,
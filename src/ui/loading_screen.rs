@@ -0,0 +1,175 @@
+use super::menu_navigation::OnMenuBack;
+use super::{get_button_bundle, get_text_bundle};
+use crate::asset_loader::LoadingProgress;
+use crate::state::GameState;
+use crate::util::despawn_components;
+use bevy::prelude::*;
+
+const BACKGROUND_COLOUR: Color = Color::rgb(0.02, 0.02, 0.05);
+const BAR_BG_COLOUR: Color = Color::rgb(0.15, 0.15, 0.15);
+const BAR_FILL_COLOUR: Color = Color::rgb(0.3, 0.7, 1.0);
+const ERROR_TEXT_COLOUR: Color = Color::rgb(1.0, 0.4, 0.4);
+const BAR_WIDTH: Val = Val::Px(400.0);
+const BAR_HEIGHT: Val = Val::Px(24.0);
+
+#[derive(Component, Debug)]
+pub struct LoadingScreenRoot;
+
+#[derive(Component, Debug)]
+pub struct LoadingFailedRoot;
+
+#[derive(Component, Debug)]
+struct ProgressBarFill;
+
+#[derive(Component, Debug)]
+struct ProgressText;
+
+#[derive(Component, Debug)]
+struct BackToMenuButton;
+
+pub struct LoadingScreenPlugin;
+
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::LoadingGame), spawn_loading_screen)
+            .add_systems(
+                Update,
+                update_loading_screen.run_if(in_state(GameState::LoadingGame)),
+            )
+            .add_systems(
+                OnExit(GameState::LoadingGame),
+                despawn_components::<LoadingScreenRoot>,
+            )
+            .add_systems(OnEnter(GameState::LoadingFailed), spawn_loading_failed)
+            .add_systems(
+                Update,
+                on_click_back_to_menu.run_if(in_state(GameState::LoadingFailed)),
+            )
+            .add_systems(
+                OnExit(GameState::LoadingFailed),
+                despawn_components::<LoadingFailedRoot>,
+            );
+    }
+}
+
+fn get_screen_bundle() -> NodeBundle {
+    return NodeBundle {
+        style: Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(12.0),
+            ..default()
+        },
+        background_color: BACKGROUND_COLOUR.into(),
+        ..default()
+    };
+}
+
+fn spawn_loading_screen(mut commands: Commands) {
+    let container: Entity = commands
+        .spawn((get_screen_bundle(), LoadingScreenRoot))
+        .id();
+
+    let title: Entity = commands
+        .spawn(get_text_bundle("Loading".to_string(), 40.0))
+        .id();
+
+    // Fill grows from the left as assets finish loading
+    let bar: Entity = commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: BAR_WIDTH,
+                height: BAR_HEIGHT,
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            background_color: BAR_BG_COLOUR.into(),
+            border_color: BorderColor(Color::BLACK),
+            ..default()
+        })
+        .id();
+    let fill: Entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: BAR_FILL_COLOUR.into(),
+                ..default()
+            },
+            ProgressBarFill,
+        ))
+        .id();
+    commands.entity(bar).push_children(&[fill]);
+
+    let text: Entity = commands
+        .spawn((get_text_bundle(String::new(), 20.0), ProgressText))
+        .id();
+
+    commands
+        .entity(container)
+        .push_children(&[title, bar, text]);
+}
+
+fn update_loading_screen(
+    progress: Res<LoadingProgress>,
+    mut fill_query: Query<&mut Style, With<ProgressBarFill>>,
+    mut text_query: Query<&mut Text, With<ProgressText>>,
+) {
+    if let Ok(mut style) = fill_query.get_single_mut() {
+        style.width = Val::Percent(progress.fraction() * 100.0);
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.sections[0].value = format!("{} / {} assets", progress.loaded, progress.total);
+    }
+}
+
+fn spawn_loading_failed(mut commands: Commands, progress: Res<LoadingProgress>) {
+    let container: Entity = commands
+        .spawn((get_screen_bundle(), LoadingFailedRoot))
+        .id();
+
+    let title: Entity = commands
+        .spawn(get_text_bundle("Failed to load assets".to_string(), 40.0))
+        .id();
+    commands.entity(container).push_children(&[title]);
+
+    for path in progress.failed.iter() {
+        let mut text_bundle: TextBundle = get_text_bundle(path.to_string(), 20.0);
+        text_bundle.text.sections[0].style.color = ERROR_TEXT_COLOUR;
+
+        let text: Entity = commands.spawn(text_bundle).id();
+        commands.entity(container).push_children(&[text]);
+    }
+
+    let button: Entity = commands.spawn((get_button_bundle(), BackToMenuButton)).id();
+    let button_text: Entity = commands
+        .spawn(get_text_bundle("Back to Menu".to_string(), 32.0))
+        .id();
+    commands.entity(button).push_children(&[button_text]);
+    commands.entity(container).push_children(&[button]);
+}
+
+fn on_click_back_to_menu(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackToMenuButton>)>,
+    mut menu_back_reader: EventReader<OnMenuBack>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if menu_back_reader.read().count() > 0 {
+        next_state.set(GameState::StartMenu);
+    }
+
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(GameState::StartMenu);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::input::{ActiveInputDevice, InputActionSet, InputDevice};
+use crate::state::GameState;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+const MENU_STATES: [GameState; 6] = [
+    GameState::StartMenu,
+    GameState::Settings,
+    GameState::Paused,
+    GameState::InGameMenu,
+    GameState::LoadingFailed,
+    GameState::SaveSlots,
+];
+
+// Sent when the gamepad's back button is pressed in a menu
+#[derive(Event, Debug)]
+pub struct OnMenuBack;
+
+// Button selected with the D-pad, pressed with the south face button
+#[derive(Resource, Debug, Default)]
+struct MenuFocus {
+    button: Option<Entity>,
+}
+
+pub struct MenuNavigationPlugin;
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>()
+            .add_event::<OnMenuBack>()
+            .add_systems(
+                PreUpdate,
+                // After the UI has handled the mouse, so the focused button's interaction wins
+                navigate_menu
+                    .after(UiSystem::Focus)
+                    .after(InputActionSet)
+                    .run_if(
+                        in_state(GameState::StartMenu)
+                            .or_else(in_state(GameState::Settings))
+                            .or_else(in_state(GameState::Paused))
+                            .or_else(in_state(GameState::InGameMenu))
+                            .or_else(in_state(GameState::LoadingFailed))
+                            .or_else(in_state(GameState::SaveSlots)),
+                    ),
+            );
+
+        // Back is handled by the menu it was pressed in, not by the one that opens after it
+        for state in MENU_STATES {
+            app.add_systems(OnExit(state), clear_menu_back);
+        }
+    }
+}
+
+fn clear_menu_back(mut events: ResMut<Events<OnMenuBack>>) {
+    events.clear();
+}
+
+fn navigate_menu(
+    active_device: Res<ActiveInputDevice>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut focus: ResMut<MenuFocus>,
+    mut button_query: Query<(Entity, &GlobalTransform, &mut Interaction), With<Button>>,
+    mut event_writer: EventWriter<OnMenuBack>,
+) {
+    // The mouse drives the buttons when the gamepad isn't in use
+    let InputDevice::Gamepad(gamepad) = active_device.device else {
+        focus.button = None;
+        return;
+    };
+
+    // Buttons in reading order, top to bottom then left to right
+    let mut buttons: Vec<(Entity, Vec3)> = button_query
+        .iter()
+        .map(|(entity, transform, _)| (entity, transform.translation()))
+        .collect();
+    buttons.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+    if buttons.is_empty() {
+        focus.button = None;
+        return;
+    }
+
+    let just_pressed = |button_type: GamepadButtonType| -> bool {
+        return gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type));
+    };
+
+    // Start on the first button, then step through them with the D-pad
+    let mut index: usize = 0;
+
+    if let Some(current) = focus
+        .button
+        .and_then(|focused| buttons.iter().position(|(entity, _)| *entity == focused))
+    {
+        index = current;
+
+        if just_pressed(GamepadButtonType::DPadDown) || just_pressed(GamepadButtonType::DPadRight) {
+            index = (index + 1) % buttons.len();
+        }
+
+        if just_pressed(GamepadButtonType::DPadUp) || just_pressed(GamepadButtonType::DPadLeft) {
+            index = (index + buttons.len() - 1) % buttons.len();
+        }
+    }
+
+    focus.button = Some(buttons[index].0);
+
+    let confirm: bool = just_pressed(GamepadButtonType::South);
+
+    for (entity, _, mut interaction) in button_query.iter_mut() {
+        let target: Interaction = if focus.button != Some(entity) {
+            Interaction::None
+        } else if confirm {
+            Interaction::Pressed
+        } else {
+            Interaction::Hovered
+        };
+
+        // Pressed only lasts for the frame the button is confirmed on
+        interaction.set_if_neq(target);
+    }
+
+    if just_pressed(GamepadButtonType::East) {
+        event_writer.send(OnMenuBack);
+    }
+}
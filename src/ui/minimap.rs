@@ -0,0 +1,199 @@
+use crate::enemy::Enemy;
+use crate::input::{ActionState, InputAction};
+use crate::level_manager::{CelestialBody, Station};
+use crate::player::Player;
+use crate::state::{GameState, SessionScoped};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+const MINIMAP_SIZE: f32 = 180.0;
+const MINIMAP_MARGIN: f32 = 10.0;
+const MINIMAP_BG_COLOUR: Color = Color::rgba(0.05, 0.08, 0.15, 0.8);
+const PLAYER_BLIP_COLOUR: Color = Color::rgb(0.3, 1.0, 0.3);
+const STATION_BLIP_COLOUR: Color = Color::rgb(0.3, 0.7, 1.0);
+const ENEMY_BLIP_COLOUR: Color = Color::rgb(1.0, 0.3, 0.3);
+const CELESTIAL_BLIP_COLOUR: Color = Color::rgb(0.9, 0.8, 0.4);
+const PLAYER_BLIP_SIZE: f32 = 8.0;
+const BLIP_SIZE: f32 = 6.0;
+
+#[derive(Resource, Debug)]
+pub struct RadarSettings {
+    // world distance from the player to the edge of the minimap
+    pub range: f32,
+    // rotate the map with the player's heading, otherwise north is always up
+    pub rotating: bool,
+}
+
+impl Default for RadarSettings {
+    fn default() -> Self {
+        Self {
+            range: 1200.0,
+            rotating: false,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct MinimapRoot;
+
+#[derive(Component, Debug)]
+struct MinimapBlip;
+
+// Blip UI node for every entity shown on the minimap
+#[derive(Resource, Debug, Default)]
+struct MinimapBlips {
+    blips: HashMap<Entity, Entity>,
+}
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RadarSettings>()
+            .init_resource::<MinimapBlips>()
+            .add_systems(OnEnter(GameState::LoadingGame), spawn_minimap)
+            .add_systems(
+                Update,
+                (toggle_radar_mode, update_minimap)
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn spawn_minimap(mut commands: Commands, mut blips: ResMut<MinimapBlips>) {
+    blips.blips.clear();
+
+    let container: Entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(MINIMAP_MARGIN),
+                    bottom: Val::Px(MINIMAP_MARGIN),
+                    width: Val::Px(MINIMAP_SIZE),
+                    height: Val::Px(MINIMAP_SIZE),
+                    border: UiRect::all(Val::Px(1.0)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                background_color: MINIMAP_BG_COLOUR.into(),
+                border_color: BorderColor(Color::BLACK),
+                ..default()
+            },
+            MinimapRoot,
+            SessionScoped,
+        ))
+        .id();
+
+    // Player is always in the centre
+    let player_blip: Entity = commands
+        .spawn(get_blip_bundle(
+            Vec2::splat(MINIMAP_SIZE / 2.0),
+            PLAYER_BLIP_SIZE,
+            PLAYER_BLIP_COLOUR,
+        ))
+        .id();
+
+    commands.entity(container).push_children(&[player_blip]);
+}
+
+fn get_blip_bundle(position: Vec2, size: f32, colour: Color) -> NodeBundle {
+    return NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(position.x - size / 2.0),
+            top: Val::Px(position.y - size / 2.0),
+            width: Val::Px(size),
+            height: Val::Px(size),
+            ..default()
+        },
+        background_color: colour.into(),
+        ..default()
+    };
+}
+
+fn toggle_radar_mode(actions: Res<ActionState>, mut settings: ResMut<RadarSettings>) {
+    if actions.just_pressed(InputAction::RadarMode) {
+        settings.rotating = !settings.rotating;
+        info!("Radar rotating: {}", settings.rotating);
+    }
+}
+
+fn update_minimap(
+    mut commands: Commands,
+    mut blips: ResMut<MinimapBlips>,
+    settings: Res<RadarSettings>,
+    root_query: Query<Entity, With<MinimapRoot>>,
+    player_query: Query<&Transform, With<Player>>,
+    target_query: Query<
+        (Entity, &Transform, Option<&Station>, Option<&Enemy>),
+        Or<(With<Station>, With<Enemy>, With<CelestialBody>)>,
+    >,
+    mut blip_query: Query<&mut Style, With<MinimapBlip>>,
+) {
+    let Ok(root) = root_query.get_single() else {
+        return;
+    };
+
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let player_position: Vec2 = player_transform.translation.truncate();
+    let heading: Quat = if settings.rotating {
+        player_transform.rotation.inverse()
+    } else {
+        Quat::IDENTITY
+    };
+    let scale: f32 = (MINIMAP_SIZE / 2.0) / settings.range;
+
+    // Remove blips for entities that no longer exist
+    blips.blips.retain(|target, blip| {
+        let exists: bool = target_query.contains(*target);
+
+        if !exists {
+            commands.entity(*blip).despawn_recursive();
+        }
+
+        return exists;
+    });
+
+    for (entity, transform, station, enemy) in target_query.iter() {
+        let colour: Color = if station.is_some() {
+            STATION_BLIP_COLOUR
+        } else if enemy.is_some() {
+            ENEMY_BLIP_COLOUR
+        } else {
+            CELESTIAL_BLIP_COLOUR
+        };
+
+        // Position relative to the player, y is flipped as UI y points down
+        let offset: Vec3 =
+            heading * (transform.translation.truncate() - player_position).extend(0.0);
+        let position: Vec2 =
+            Vec2::new(offset.x, -offset.y) * scale + Vec2::splat(MINIMAP_SIZE / 2.0);
+        let in_range: bool = offset.truncate().length() <= settings.range;
+
+        let Some(blip) = blips.blips.get(&entity).copied() else {
+            let blip: Entity = commands
+                .spawn((get_blip_bundle(position, BLIP_SIZE, colour), MinimapBlip))
+                .id();
+            commands.entity(root).push_children(&[blip]);
+            blips.blips.insert(entity, blip);
+            continue;
+        };
+
+        let Ok(mut style) = blip_query.get_mut(blip) else {
+            continue;
+        };
+
+        style.left = Val::Px(position.x - BLIP_SIZE / 2.0);
+        style.top = Val::Px(position.y - BLIP_SIZE / 2.0);
+        style.display = if in_range {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
@@ -0,0 +1,240 @@
+use crate::camera::MainCamera;
+use crate::enemy::Enemy;
+use crate::level_manager::Station;
+use crate::player::Player;
+use crate::schedule::RenderSet;
+use crate::state::{GameState, SessionScoped};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+const INDICATOR_SIZE: f32 = 48.0;
+// Distance in pixels from the edge of the screen to the centre of an indicator
+const EDGE_MARGIN: f32 = 32.0;
+const ARROW_FONT_SIZE: f32 = 24.0;
+const LABEL_FONT_SIZE: f32 = 14.0;
+const STATION_INDICATOR_COLOUR: Color = Color::rgb(0.3, 0.7, 1.0);
+const ENEMY_INDICATOR_COLOUR: Color = Color::rgb(1.0, 0.3, 0.3);
+const STATION_ICON: &str = "S";
+const ENEMY_ICON: &str = "!";
+
+#[derive(Component, Debug)]
+pub struct IndicatorRoot;
+
+#[derive(Component, Debug)]
+struct IndicatorArrow;
+
+#[derive(Component, Debug)]
+struct IndicatorLabel;
+
+#[derive(Debug, Clone, Copy)]
+struct Indicator {
+    node: Entity,
+    arrow: Entity,
+    label: Entity,
+}
+
+// Indicator UI nodes for every entity that can be pointed at
+#[derive(Resource, Debug, Default)]
+struct Indicators {
+    indicators: HashMap<Entity, Indicator>,
+}
+
+pub struct OffscreenIndicatorPlugin;
+
+impl Plugin for OffscreenIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Indicators>()
+            .add_systems(OnEnter(GameState::LoadingGame), spawn_indicator_root)
+            .add_systems(
+                Update,
+                update_indicators
+                    .after(RenderSet::CameraEffects)
+                    .run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+fn spawn_indicator_root(mut commands: Commands, mut indicators: ResMut<Indicators>) {
+    indicators.indicators.clear();
+
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            ..default()
+        },
+        IndicatorRoot,
+        SessionScoped,
+    ));
+}
+
+fn spawn_indicator(commands: &mut Commands, root: Entity, icon: &str, colour: Color) -> Indicator {
+    let node: Entity = commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                width: Val::Px(INDICATOR_SIZE),
+                height: Val::Px(INDICATOR_SIZE),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+
+    // Arrow points right and is rotated towards the target
+    let arrow: Entity = commands
+        .spawn((
+            TextBundle::from_section(
+                ">",
+                TextStyle {
+                    font_size: ARROW_FONT_SIZE,
+                    color: colour,
+                    ..default()
+                },
+            ),
+            IndicatorArrow,
+        ))
+        .id();
+
+    let label: Entity = commands
+        .spawn((
+            TextBundle::from_sections([
+                TextSection::new(
+                    format!("{} ", icon),
+                    TextStyle {
+                        font_size: LABEL_FONT_SIZE,
+                        color: colour,
+                        ..default()
+                    },
+                ),
+                TextSection::new(
+                    "",
+                    TextStyle {
+                        font_size: LABEL_FONT_SIZE,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+            ]),
+            IndicatorLabel,
+        ))
+        .id();
+
+    commands.entity(node).push_children(&[arrow, label]);
+    commands.entity(root).push_children(&[node]);
+
+    return Indicator { node, arrow, label };
+}
+
+fn update_indicators(
+    mut commands: Commands,
+    mut indicators: ResMut<Indicators>,
+    root_query: Query<Entity, With<IndicatorRoot>>,
+    camera_query: Query<(&Camera, &Transform), With<MainCamera>>,
+    player_query: Query<&Transform, With<Player>>,
+    target_query: Query<(Entity, &Transform, Option<&Station>), Or<(With<Station>, With<Enemy>)>>,
+    mut node_query: Query<&mut Style>,
+    mut arrow_query: Query<
+        &mut Transform,
+        (
+            With<IndicatorArrow>,
+            Without<MainCamera>,
+            Without<Player>,
+            Without<Station>,
+            Without<Enemy>,
+        ),
+    >,
+    mut label_query: Query<&mut Text, With<IndicatorLabel>>,
+) {
+    let Ok(root) = root_query.get_single() else {
+        return;
+    };
+
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    let Some(screen_size) = camera.logical_viewport_size() else {
+        return;
+    };
+
+    // The camera has no parent, so its transform is already final for this frame, unlike its
+    // GlobalTransform which isn't updated until PostUpdate
+    let camera_global: GlobalTransform = GlobalTransform::from(*camera_transform);
+    let origin: Vec2 = player_query
+        .get_single()
+        .map(|transform| transform.translation.truncate())
+        .unwrap_or(camera_transform.translation.truncate());
+
+    // Remove indicators for entities that no longer exist
+    indicators.indicators.retain(|target, indicator| {
+        let exists: bool = target_query.contains(*target);
+
+        if !exists {
+            commands.entity(indicator.node).despawn_recursive();
+        }
+
+        return exists;
+    });
+
+    let centre: Vec2 = screen_size / 2.0;
+
+    for (entity, transform, station) in target_query.iter() {
+        let Some(indicator) = indicators.indicators.get(&entity).copied() else {
+            let indicator: Indicator = match station {
+                Some(_) => {
+                    spawn_indicator(&mut commands, root, STATION_ICON, STATION_INDICATOR_COLOUR)
+                }
+                None => spawn_indicator(&mut commands, root, ENEMY_ICON, ENEMY_INDICATOR_COLOUR),
+            };
+            indicators.indicators.insert(entity, indicator);
+            continue;
+        };
+
+        let Ok(mut style) = node_query.get_mut(indicator.node) else {
+            continue;
+        };
+
+        let Some(screen_position) = camera.world_to_viewport(&camera_global, transform.translation)
+        else {
+            style.display = Display::None;
+            continue;
+        };
+
+        let on_screen: bool =
+            screen_position.cmpge(Vec2::ZERO).all() && screen_position.cmple(screen_size).all();
+
+        if on_screen {
+            style.display = Display::None;
+            continue;
+        }
+
+        // Scale the direction from the centre so it just reaches the margin on the nearest edge
+        let direction: Vec2 = screen_position - centre;
+        let limit: Vec2 = (centre - Vec2::splat(EDGE_MARGIN)).max(Vec2::ZERO);
+        let scale: f32 = (limit.x / direction.x.abs()).min(limit.y / direction.y.abs());
+        let edge_position: Vec2 = centre + direction * scale;
+
+        style.display = Display::Flex;
+        style.left = Val::Px(edge_position.x - INDICATOR_SIZE / 2.0);
+        style.top = Val::Px(edge_position.y - INDICATOR_SIZE / 2.0);
+
+        // UI y points down, so a positive angle turns clockwise on screen
+        if let Ok(mut arrow_transform) = arrow_query.get_mut(indicator.arrow) {
+            arrow_transform.rotation = Quat::from_rotation_z(direction.y.atan2(direction.x));
+        }
+
+        if let Ok(mut text) = label_query.get_mut(indicator.label) {
+            let distance: f32 = origin.distance(transform.translation.truncate());
+            text.sections[1].value = format!("{:.0}", distance);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use super::menu_navigation::OnMenuBack;
+use super::save_slots_menu::{SaveSlotsMenu, SlotMenuMode};
+use super::settings_menu::SettingsReturnState;
+use super::{get_button_bundle, get_text_bundle};
+use crate::state::GameState;
+use crate::util::despawn_components;
+use bevy::prelude::*;
+
+const BACKDROP_COLOUR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+#[derive(Component, Debug)]
+pub struct PauseMenuRoot;
+
+#[derive(Component, Debug)]
+struct ResumeButton;
+
+#[derive(Component, Debug)]
+struct SettingsButton;
+
+#[derive(Component, Debug)]
+struct SaveButton;
+
+#[derive(Component, Debug)]
+struct QuitButton;
+
+pub struct PauseMenuPlugin;
+
+impl Plugin for PauseMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Paused), spawn_pause_menu)
+            .add_systems(
+                Update,
+                (
+                    on_click_resume,
+                    resume.run_if(on_event::<OnMenuBack>()),
+                    on_click_settings,
+                    on_click_save,
+                    on_click_quit,
+                )
+                    .run_if(in_state(GameState::Paused)),
+            )
+            .add_systems(
+                OnExit(GameState::Paused),
+                despawn_components::<PauseMenuRoot>,
+            );
+    }
+}
+
+fn spawn_pause_menu(mut commands: Commands) {
+    // Create and spawn a dimmed backdrop over the game to hold the buttons
+    let container = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: BACKDROP_COLOUR.into(),
+                ..default()
+            },
+            PauseMenuRoot,
+        ))
+        .id();
+
+    let title = commands
+        .spawn(get_text_bundle("Paused".to_string(), 60.0))
+        .id();
+    commands.entity(container).push_children(&[title]);
+
+    spawn_button(&mut commands, container, "Resume", ResumeButton);
+    spawn_button(&mut commands, container, "Settings", SettingsButton);
+    spawn_button(&mut commands, container, "Save", SaveButton);
+    spawn_button(&mut commands, container, "Quit to Menu", QuitButton);
+}
+
+fn spawn_button(commands: &mut Commands, container: Entity, label: &str, marker: impl Component) {
+    let button = commands.spawn((get_button_bundle(), marker)).id();
+    let button_text = commands
+        .spawn(get_text_bundle(label.to_string(), 40.0))
+        .id();
+    commands.entity(button).push_children(&[button_text]);
+    commands.entity(container).push_children(&[button]);
+}
+
+fn on_click_resume(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ResumeButton>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(GameState::InGame);
+        }
+    }
+}
+
+fn resume(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::InGame);
+}
+
+fn on_click_settings(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<SettingsButton>)>,
+    mut return_state: ResMut<SettingsReturnState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            return_state.0 = GameState::Paused;
+            next_state.set(GameState::Settings);
+        }
+    }
+}
+
+fn on_click_save(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<SaveButton>)>,
+    mut slots_menu: ResMut<SaveSlotsMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            *slots_menu = SaveSlotsMenu {
+                mode: SlotMenuMode::Save,
+                return_to: GameState::Paused,
+            };
+            next_state.set(GameState::SaveSlots);
+        }
+    }
+}
+
+fn on_click_quit(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(GameState::StartMenu);
+        }
+    }
+}
@@ -0,0 +1,482 @@
+use super::menu_navigation::OnMenuBack;
+use super::{get_button_bundle, get_text_bundle, spawn_ui_row};
+use crate::save_game::{
+    delete_slot, read_slot_metadata, rename_slot, slot_ids, OnLoadFailed, OnLoadGame,
+    OnSlotsChanged, SaveMetadata, AUTOSAVE_SLOT,
+};
+use crate::state::{GameState, OnSaveGame};
+use crate::util::despawn_components;
+use bevy::prelude::*;
+use bevy::window::ReceivedCharacter;
+
+const MENU_BG_COLOUR: Color = Color::hsl(227., 0.37, 0.22);
+const DETAIL_TEXT_COLOUR: Color = Color::rgb(0.65, 0.65, 0.7);
+const ERROR_TEXT_COLOUR: Color = Color::rgb(1.0, 0.4, 0.4);
+const ROW_WIDTH: Val = Val::Px(900.0);
+const ACTION_BUTTON_WIDTH: Val = Val::Px(120.0);
+const ACTION_BUTTON_HEIGHT: Val = Val::Px(32.0);
+const MAX_NAME_LENGTH: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotMenuMode {
+    Load,
+    Save,
+}
+
+// How the save slot screen was opened and where to go when it's closed
+#[derive(Resource, Debug)]
+pub struct SaveSlotsMenu {
+    pub mode: SlotMenuMode,
+    pub return_to: GameState,
+}
+
+impl Default for SaveSlotsMenu {
+    fn default() -> Self {
+        Self {
+            mode: SlotMenuMode::Load,
+            return_to: GameState::StartMenu,
+        }
+    }
+}
+
+// Slot currently being renamed and the name typed so far
+#[derive(Resource, Debug, Default)]
+struct RenameState {
+    slot: Option<String>,
+    buffer: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotAction {
+    Load,
+    Save,
+    Rename,
+    Delete,
+}
+
+impl SlotAction {
+    fn label(&self, has_save: bool) -> &'static str {
+        match self {
+            SlotAction::Load => "Load",
+            SlotAction::Save if has_save => "Overwrite",
+            SlotAction::Save => "Save",
+            SlotAction::Rename => "Rename",
+            SlotAction::Delete => "Delete",
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct SaveSlotsRoot;
+
+// Holds the slot rows, rebuilt whenever the slots change
+#[derive(Component, Debug)]
+struct SlotList;
+
+#[derive(Component, Debug)]
+struct SlotActionButton {
+    slot: String,
+    action: SlotAction,
+}
+
+#[derive(Component, Debug)]
+struct SlotsMessage;
+
+#[derive(Component, Debug)]
+struct BackButton;
+
+pub struct SaveSlotsMenuPlugin;
+
+impl Plugin for SaveSlotsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveSlotsMenu>()
+            .init_resource::<RenameState>()
+            .add_systems(OnEnter(GameState::SaveSlots), spawn_save_slots_menu)
+            .add_systems(
+                Update,
+                (
+                    on_click_back,
+                    on_click_slot_action,
+                    rename_input,
+                    show_load_failed.run_if(on_event::<OnLoadFailed>()),
+                    refresh_slot_list.run_if(
+                        on_event::<OnSlotsChanged>().or_else(resource_changed::<RenameState>()),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::SaveSlots)),
+            )
+            .add_systems(
+                OnExit(GameState::SaveSlots),
+                (despawn_components::<SaveSlotsRoot>, clear_rename),
+            );
+    }
+}
+
+fn spawn_save_slots_menu(
+    mut commands: Commands,
+    menu: Res<SaveSlotsMenu>,
+    rename_state: Res<RenameState>,
+) {
+    // Create and spawn main UI container
+    let container: Entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                background_color: MENU_BG_COLOUR.into(),
+                ..default()
+            },
+            SaveSlotsRoot,
+        ))
+        .id();
+
+    let title: &str = match menu.mode {
+        SlotMenuMode::Load => "Load Game",
+        SlotMenuMode::Save => "Save Game",
+    };
+    let title: Entity = commands
+        .spawn(get_text_bundle(title.to_string(), 40.0))
+        .id();
+
+    let list: Entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            },
+            SlotList,
+        ))
+        .id();
+    spawn_slot_rows(&mut commands, list, &menu, &rename_state);
+
+    let message: Entity = commands
+        .spawn((get_text_bundle(String::new(), 20.0), SlotsMessage))
+        .id();
+
+    // Create and spawn Back Button
+    let button: Entity = commands.spawn((get_button_bundle(), BackButton)).id();
+    let button_text: Entity = commands
+        .spawn(get_text_bundle("Back".to_string(), 40.0))
+        .id();
+    commands.entity(button).push_children(&[button_text]);
+
+    commands
+        .entity(container)
+        .push_children(&[title, list, message, button]);
+}
+
+fn spawn_slot_rows(
+    commands: &mut Commands,
+    list: Entity,
+    menu: &SaveSlotsMenu,
+    rename_state: &RenameState,
+) {
+    for slot in slot_ids() {
+        let (name, details, details_colour, has_save) = match read_slot_metadata(&slot) {
+            Ok(Some(metadata)) => (
+                metadata.name.clone(),
+                describe_metadata(&metadata),
+                DETAIL_TEXT_COLOUR,
+                true,
+            ),
+            Ok(None) => (
+                String::new(),
+                "Empty".to_string(),
+                DETAIL_TEXT_COLOUR,
+                false,
+            ),
+            // Still allow deleting a slot that can't be read
+            Err(error) => (
+                String::new(),
+                format!("Unreadable: {}", error),
+                ERROR_TEXT_COLOUR,
+                true,
+            ),
+        };
+
+        let renaming: bool = rename_state.slot.as_deref() == Some(slot.as_str());
+        let name: String = if renaming {
+            format!("{}_", rename_state.buffer)
+        } else if name.is_empty() {
+            slot.clone()
+        } else {
+            name
+        };
+
+        let row: Entity = spawn_ui_row(commands, ROW_WIDTH, Val::Auto);
+
+        let info: Entity = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        let name_text: Entity = commands.spawn(get_text_bundle(name, 24.0)).id();
+        let mut details_bundle: TextBundle = get_text_bundle(details, 16.0);
+        details_bundle.text.sections[0].style.color = details_colour;
+        let details_text: Entity = commands.spawn(details_bundle).id();
+        commands
+            .entity(info)
+            .push_children(&[name_text, details_text]);
+
+        let buttons: Entity = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Row,
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+
+        let mut actions: Vec<SlotAction> = Vec::new();
+
+        match menu.mode {
+            SlotMenuMode::Load if has_save => actions.push(SlotAction::Load),
+            // The autosave slot is only written by autosaves
+            SlotMenuMode::Save if slot != AUTOSAVE_SLOT => actions.push(SlotAction::Save),
+            _ => (),
+        }
+
+        if has_save {
+            actions.push(SlotAction::Rename);
+            actions.push(SlotAction::Delete);
+        }
+
+        for action in actions {
+            let mut button_bundle: ButtonBundle = get_button_bundle();
+            button_bundle.style.width = ACTION_BUTTON_WIDTH;
+            button_bundle.style.height = ACTION_BUTTON_HEIGHT;
+
+            let button: Entity = commands
+                .spawn((
+                    button_bundle,
+                    SlotActionButton {
+                        slot: slot.clone(),
+                        action,
+                    },
+                ))
+                .id();
+            let button_text: Entity = commands
+                .spawn(get_text_bundle(action.label(has_save).to_string(), 18.0))
+                .id();
+            commands.entity(button).push_children(&[button_text]);
+            commands.entity(buttons).push_children(&[button]);
+        }
+
+        commands.entity(row).push_children(&[info, buttons]);
+        commands.entity(list).push_children(&[row]);
+    }
+}
+
+fn describe_metadata(metadata: &SaveMetadata) -> String {
+    return format!(
+        "{}  |  {}  |  {} cr  |  {}",
+        format_timestamp(metadata.timestamp),
+        metadata.location,
+        metadata.credits,
+        format_playtime(metadata.playtime_seconds),
+    );
+}
+
+// Unix timestamp as a UTC date and time
+fn format_timestamp(timestamp: u64) -> String {
+    let days: i64 = (timestamp / 86_400) as i64;
+    let seconds_of_day: u64 = timestamp % 86_400;
+
+    // Converts days since 1970-01-01 to a civil date, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z: i64 = days + 719_468;
+    let era: i64 = z.div_euclid(146_097);
+    let day_of_era: i64 = z - era * 146_097;
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index: i64 = (5 * day_of_year + 2) / 153;
+    let day: i64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month: i64 = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year: i64 = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60
+    );
+}
+
+fn format_playtime(seconds: f64) -> String {
+    let minutes: u64 = (seconds / 60.0) as u64;
+
+    return format!("{}h {:02}m", minutes / 60, minutes % 60);
+}
+
+fn refresh_slot_list(
+    mut commands: Commands,
+    mut event_reader: EventReader<OnSlotsChanged>,
+    list_query: Query<Entity, With<SlotList>>,
+    menu: Res<SaveSlotsMenu>,
+    rename_state: Res<RenameState>,
+) {
+    event_reader.clear();
+
+    let Ok(list) = list_query.get_single() else {
+        return;
+    };
+
+    commands.entity(list).despawn_descendants();
+    spawn_slot_rows(&mut commands, list, &menu, &rename_state);
+}
+
+fn on_click_slot_action(
+    interaction_query: Query<(&Interaction, &SlotActionButton), Changed<Interaction>>,
+    mut rename_state: ResMut<RenameState>,
+    mut load_writer: EventWriter<OnLoadGame>,
+    mut save_writer: EventWriter<OnSaveGame>,
+    mut changed_writer: EventWriter<OnSlotsChanged>,
+    mut message_query: Query<&mut Text, With<SlotsMessage>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let slot: String = button.slot.clone();
+
+        match button.action {
+            SlotAction::Load => load_writer.send(OnLoadGame { slot }),
+            SlotAction::Save => save_writer.send(OnSaveGame { slot }),
+            SlotAction::Rename => {
+                let name: String = read_slot_metadata(&slot)
+                    .ok()
+                    .flatten()
+                    .map(|metadata| metadata.name)
+                    .unwrap_or_default();
+
+                rename_state.slot = Some(slot);
+                rename_state.buffer = name;
+            }
+            SlotAction::Delete => {
+                if let Err(error) = delete_slot(&slot) {
+                    error!("Failed to delete {}, {}", slot, error);
+
+                    if let Ok(mut text) = message_query.get_single_mut() {
+                        text.sections[0].value = format!("Couldn't delete save: {}", error);
+                    }
+                }
+
+                changed_writer.send(OnSlotsChanged);
+            }
+        }
+    }
+}
+
+fn rename_input(
+    mut rename_state: ResMut<RenameState>,
+    mut character_reader: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut changed_writer: EventWriter<OnSlotsChanged>,
+    mut message_query: Query<&mut Text, With<SlotsMessage>>,
+) {
+    let Some(slot) = rename_state.slot.clone() else {
+        character_reader.clear();
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        *rename_state = RenameState::default();
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        let name: String = rename_state.buffer.trim().to_string();
+
+        if !name.is_empty() {
+            if let Err(error) = rename_slot(&slot, &name) {
+                error!("Failed to rename {}, {}", slot, error);
+
+                if let Ok(mut text) = message_query.get_single_mut() {
+                    text.sections[0].value = format!("Couldn't rename save: {}", error);
+                }
+            }
+        }
+
+        *rename_state = RenameState::default();
+        changed_writer.send(OnSlotsChanged);
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        rename_state.buffer.pop();
+    }
+
+    for event in character_reader.read() {
+        if !event.char.is_control() && rename_state.buffer.chars().count() < MAX_NAME_LENGTH {
+            rename_state.buffer.push(event.char);
+        }
+    }
+}
+
+fn show_load_failed(
+    mut event_reader: EventReader<OnLoadFailed>,
+    mut message_query: Query<&mut Text, With<SlotsMessage>>,
+) {
+    let Ok(mut text) = message_query.get_single_mut() else {
+        return;
+    };
+
+    for event in event_reader.read() {
+        text.sections[0].value = format!("Couldn't load game: {}", event.message);
+    }
+}
+
+fn on_click_back(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut menu_back_reader: EventReader<OnMenuBack>,
+    rename_state: Res<RenameState>,
+    menu: Res<SaveSlotsMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let clicked: bool = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    let backed: bool = menu_back_reader.read().count() > 0;
+    // Escape cancels a rename before it closes the screen
+    let escaped: bool =
+        (keyboard_input.just_pressed(KeyCode::Escape) || backed) && rename_state.slot.is_none();
+
+    if clicked || escaped {
+        next_state.set(menu.return_to);
+    }
+}
+
+fn clear_rename(mut rename_state: ResMut<RenameState>) {
+    *rename_state = RenameState::default();
+}
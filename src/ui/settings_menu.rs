@@ -0,0 +1,494 @@
+use super::menu_navigation::OnMenuBack;
+use super::{get_button_bundle, get_text_bundle, spawn_ui_row, TEXT_COLOUR};
+use crate::input::{InputAction, InputBinding, InputBindings};
+use crate::settings::{save_settings, ControlScheme, GameSettings, Setting};
+use crate::state::GameState;
+use crate::util::despawn_components;
+use bevy::prelude::*;
+
+const MENU_BG_COLOUR: Color = Color::hsl(227., 0.37, 0.22);
+const CONFLICT_COLOUR: Color = Color::rgb(1.0, 0.4, 0.4);
+const ROW_WIDTH: Val = Val::Px(520.0);
+const SETTING_BUTTON_HEIGHT: Val = Val::Px(36.0);
+const TITLE_FONT_SIZE: f32 = 40.0;
+const SECTION_FONT_SIZE: f32 = 26.0;
+const ROW_FONT_SIZE: f32 = 20.0;
+
+const SECTIONS: [(&str, &[Setting]); 3] = [
+    ("Audio", &[Setting::MasterVolume]),
+    (
+        "Display",
+        &[Setting::WindowMode, Setting::Vsync, Setting::Resolution],
+    ),
+    (
+        "Gameplay",
+        &[
+            Setting::Difficulty,
+            Setting::ScreenShake,
+            Setting::ControlScheme,
+            Setting::ThrustSpace,
+            Setting::StickDeadzone,
+        ],
+    ),
+];
+
+// State to go back to when the settings screen is closed
+#[derive(Resource, Debug)]
+pub struct SettingsReturnState(pub GameState);
+
+impl Default for SettingsReturnState {
+    fn default() -> Self {
+        Self(GameState::StartMenu)
+    }
+}
+
+// Action waiting for the next key or mouse button press to bind to it
+#[derive(Resource, Debug, Default)]
+struct RebindState {
+    action: Option<InputAction>,
+}
+
+#[derive(Component, Debug)]
+pub struct SettingsMenuRoot;
+
+#[derive(Component, Debug)]
+struct SettingButton {
+    setting: Setting,
+}
+
+#[derive(Component, Debug)]
+struct SettingValueText {
+    setting: Setting,
+}
+
+#[derive(Component, Debug)]
+struct BindingButton {
+    action: InputAction,
+}
+
+#[derive(Component, Debug)]
+struct BindingValueText {
+    action: InputAction,
+}
+
+#[derive(Component, Debug)]
+struct BindingHintText;
+
+#[derive(Component, Debug)]
+struct ResetBindingsButton;
+
+#[derive(Component, Debug)]
+struct BackButton;
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsReturnState>()
+            .init_resource::<RebindState>()
+            .add_systems(OnEnter(GameState::Settings), spawn_settings_menu)
+            .add_systems(
+                Update,
+                (
+                    on_click_back,
+                    // Before the click handlers, so a click that cancels a rebind isn't captured
+                    listen_for_binding,
+                    on_click_setting,
+                    on_click_binding,
+                    on_click_reset_bindings,
+                    update_setting_values.run_if(resource_changed::<GameSettings>()),
+                    update_binding_values.run_if(
+                        resource_changed::<GameSettings>()
+                            .or_else(resource_changed::<RebindState>()),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Settings)),
+            )
+            .add_systems(
+                OnExit(GameState::Settings),
+                (
+                    despawn_components::<SettingsMenuRoot>,
+                    save_settings,
+                    cancel_rebind,
+                ),
+            );
+    }
+}
+
+fn spawn_settings_menu(mut commands: Commands, settings: Res<GameSettings>) {
+    // Create and spawn main UI container
+    let container: Entity = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: MENU_BG_COLOUR.into(),
+                ..default()
+            },
+            SettingsMenuRoot,
+        ))
+        .id();
+
+    let title: Entity = commands
+        .spawn(get_text_bundle("Settings".to_string(), TITLE_FONT_SIZE))
+        .id();
+
+    // Options on the left, controls on the right
+    let columns: Entity = commands
+        .spawn(NodeBundle {
+            style: Style {
+                display: Display::Flex,
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(40.0),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    let options_column: Entity = commands.spawn(get_column_bundle()).id();
+    let controls_column: Entity = commands.spawn(get_column_bundle()).id();
+    commands
+        .entity(columns)
+        .push_children(&[options_column, controls_column]);
+    commands.entity(container).push_children(&[title, columns]);
+
+    for (section, section_settings) in SECTIONS {
+        let header: Entity = commands
+            .spawn(get_text_bundle(section.to_string(), SECTION_FONT_SIZE))
+            .id();
+        commands.entity(options_column).push_children(&[header]);
+
+        for setting in section_settings {
+            let row: Entity = spawn_setting_row(&mut commands, &settings, *setting);
+            commands.entity(options_column).push_children(&[row]);
+        }
+    }
+
+    let header: Entity = commands
+        .spawn(get_text_bundle("Controls".to_string(), SECTION_FONT_SIZE))
+        .id();
+    commands.entity(controls_column).push_children(&[header]);
+
+    for action in InputAction::ALL {
+        let row: Entity = spawn_binding_row(&mut commands, &settings, action);
+        commands.entity(controls_column).push_children(&[row]);
+    }
+
+    let hint_text: Entity = commands
+        .spawn((
+            get_text_bundle(binding_hint(&settings, None), ROW_FONT_SIZE),
+            BindingHintText,
+        ))
+        .id();
+
+    let mut reset_bundle: ButtonBundle = get_button_bundle();
+    reset_bundle.style.height = SETTING_BUTTON_HEIGHT;
+
+    let reset_button: Entity = commands.spawn((reset_bundle, ResetBindingsButton)).id();
+    let reset_text: Entity = commands
+        .spawn(get_text_bundle("Reset Controls".to_string(), ROW_FONT_SIZE))
+        .id();
+    commands.entity(reset_button).push_children(&[reset_text]);
+    commands
+        .entity(controls_column)
+        .push_children(&[hint_text, reset_button]);
+
+    // Create and spawn Back Button
+    let mut button_bundle: ButtonBundle = get_button_bundle();
+    button_bundle.style.height = SETTING_BUTTON_HEIGHT;
+
+    let button: Entity = commands.spawn((button_bundle, BackButton)).id();
+    let button_text: Entity = commands
+        .spawn(get_text_bundle("Back".to_string(), SECTION_FONT_SIZE))
+        .id();
+    commands.entity(button).push_children(&[button_text]);
+    commands.entity(container).push_children(&[button]);
+}
+
+fn get_column_bundle() -> NodeBundle {
+    return NodeBundle {
+        style: Style {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        ..default()
+    };
+}
+
+fn spawn_setting_row(commands: &mut Commands, settings: &GameSettings, setting: Setting) -> Entity {
+    let row: Entity = spawn_ui_row(commands, ROW_WIDTH, Val::Auto);
+
+    let label: Entity = commands
+        .spawn(get_text_bundle(setting.label().to_string(), ROW_FONT_SIZE))
+        .id();
+
+    // Clicking the value steps it on to the next option
+    let mut button_bundle: ButtonBundle = get_button_bundle();
+    button_bundle.style.height = SETTING_BUTTON_HEIGHT;
+
+    let button: Entity = commands
+        .spawn((button_bundle, SettingButton { setting }))
+        .id();
+    let value_text: Entity = commands
+        .spawn((
+            get_text_bundle(settings.value_label(setting), ROW_FONT_SIZE),
+            SettingValueText { setting },
+        ))
+        .id();
+
+    commands.entity(button).push_children(&[value_text]);
+    commands.entity(row).push_children(&[label, button]);
+
+    return row;
+}
+
+fn spawn_binding_row(
+    commands: &mut Commands,
+    settings: &GameSettings,
+    action: InputAction,
+) -> Entity {
+    let row: Entity = spawn_ui_row(commands, ROW_WIDTH, Val::Auto);
+
+    let label: Entity = commands
+        .spawn(get_text_bundle(action.label().to_string(), ROW_FONT_SIZE))
+        .id();
+
+    // Clicking the binding listens for a new key
+    let mut button_bundle: ButtonBundle = get_button_bundle();
+    button_bundle.style.height = SETTING_BUTTON_HEIGHT;
+
+    let button: Entity = commands
+        .spawn((button_bundle, BindingButton { action }))
+        .id();
+    let value_text: Entity = commands
+        .spawn((
+            get_binding_text_bundle(settings, None, action),
+            BindingValueText { action },
+        ))
+        .id();
+
+    commands.entity(button).push_children(&[value_text]);
+    commands.entity(row).push_children(&[label, button]);
+
+    return row;
+}
+
+fn get_binding_text_bundle(
+    settings: &GameSettings,
+    listening: Option<InputAction>,
+    action: InputAction,
+) -> TextBundle {
+    let mut text_bundle: TextBundle = get_text_bundle(String::new(), ROW_FONT_SIZE);
+    set_binding_text(&mut text_bundle.text, settings, listening, action);
+
+    return text_bundle;
+}
+
+fn set_binding_text(
+    text: &mut Text,
+    settings: &GameSettings,
+    listening: Option<InputAction>,
+    action: InputAction,
+) {
+    text.sections[0].value = if listening == Some(action) {
+        "...".to_string()
+    } else {
+        settings.controls.binding(action).label()
+    };
+    text.sections[0].style.color = if settings
+        .controls
+        .conflicts(action, settings.gameplay.control_scheme)
+        .is_empty()
+    {
+        TEXT_COLOUR
+    } else {
+        CONFLICT_COLOUR
+    };
+}
+
+// Prompt while listening for a key, otherwise lists any actions sharing a binding
+fn binding_hint(settings: &GameSettings, listening: Option<InputAction>) -> String {
+    if let Some(action) = listening {
+        return format!(
+            "Press a key or mouse button for {}, click any button to cancel",
+            action.label()
+        );
+    }
+
+    let mut reported: Vec<InputBinding> = Vec::new();
+    let mut conflicts: Vec<String> = Vec::new();
+
+    let scheme: ControlScheme = settings.gameplay.control_scheme;
+
+    for action in InputAction::ALL {
+        for binding in settings.controls.triggers(action, scheme) {
+            if reported.contains(&binding) {
+                continue;
+            }
+
+            let names: Vec<&str> = InputAction::ALL
+                .into_iter()
+                .filter(|other| {
+                    settings
+                        .controls
+                        .triggers(*other, scheme)
+                        .contains(&binding)
+                })
+                .map(|other| other.label())
+                .collect();
+
+            if names.len() < 2 {
+                continue;
+            }
+
+            reported.push(binding);
+            conflicts.push(format!("{} share {}", names.join(", "), binding.label()));
+        }
+    }
+
+    return conflicts.join("\n");
+}
+
+fn on_click_setting(
+    mut rebind_state: ResMut<RebindState>,
+    mut settings: ResMut<GameSettings>,
+    interaction_query: Query<(&Interaction, &SettingButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            settings.cycle(button.setting);
+            rebind_state.action = None;
+        }
+    }
+}
+
+fn update_setting_values(
+    settings: Res<GameSettings>,
+    mut text_query: Query<(&mut Text, &SettingValueText)>,
+) {
+    for (mut text, value_text) in text_query.iter_mut() {
+        text.sections[0].value = settings.value_label(value_text.setting);
+    }
+}
+
+fn on_click_binding(
+    mut rebind_state: ResMut<RebindState>,
+    interaction_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        // Clicking the binding being changed again cancels it
+        if rebind_state.action == Some(button.action) {
+            rebind_state.action = None;
+        } else {
+            rebind_state.action = Some(button.action);
+        }
+    }
+}
+
+fn listen_for_binding(
+    mut rebind_state: ResMut<RebindState>,
+    mut settings: ResMut<GameSettings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    button_query: Query<&Interaction, With<Button>>,
+) {
+    let Some(action) = rebind_state.action else {
+        return;
+    };
+
+    // Clicks on the menu's own buttons are left to their handlers, which cancel the rebind
+    let over_button: bool = button_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+
+    let binding: Option<InputBinding> = keyboard_input
+        .get_just_pressed()
+        .next()
+        .map(|key| InputBinding::Key(*key))
+        .or_else(|| {
+            mouse_input
+                .get_just_pressed()
+                .next()
+                .filter(|_| !over_button)
+                .map(|button| InputBinding::Mouse(*button))
+        });
+
+    if let Some(binding) = binding {
+        settings.controls.bind(action, binding);
+        rebind_state.action = None;
+    }
+}
+
+fn on_click_reset_bindings(
+    mut rebind_state: ResMut<RebindState>,
+    mut settings: ResMut<GameSettings>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ResetBindingsButton>)>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Pressed {
+            settings.controls = InputBindings::default();
+            rebind_state.action = None;
+        }
+    }
+}
+
+fn update_binding_values(
+    settings: Res<GameSettings>,
+    rebind_state: Res<RebindState>,
+    mut value_query: Query<(&mut Text, &BindingValueText), Without<BindingHintText>>,
+    mut hint_query: Query<&mut Text, With<BindingHintText>>,
+) {
+    for (mut text, value_text) in value_query.iter_mut() {
+        set_binding_text(&mut text, &settings, rebind_state.action, value_text.action);
+    }
+
+    if let Ok(mut text) = hint_query.get_single_mut() {
+        text.sections[0].value = binding_hint(&settings, rebind_state.action);
+    }
+}
+
+fn cancel_rebind(mut rebind_state: ResMut<RebindState>) {
+    rebind_state.action = None;
+}
+
+fn on_click_back(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<BackButton>)>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut menu_back_reader: EventReader<OnMenuBack>,
+    mut rebind_state: ResMut<RebindState>,
+    return_state: Res<SettingsReturnState>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let clicked: bool = interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed);
+    let backed: bool = menu_back_reader.read().count() > 0;
+
+    // Escape can be bound, so while listening it's left for the rebind
+    if rebind_state.action.is_some() {
+        if clicked || backed {
+            rebind_state.action = None;
+        }
+
+        return;
+    }
+
+    if clicked || backed || keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(return_state.0);
+    }
+}
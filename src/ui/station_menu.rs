@@ -1,5 +1,7 @@
+use super::menu_navigation::OnMenuBack;
 use super::{get_flex_child, get_text_bundle, spawn_ui_col, spawn_ui_row};
-use crate::level_manager::{OnStationClicked, Station};
+use crate::docking::OnStationMenuOpen;
+use crate::level_manager::Station;
 use crate::state::GameState;
 use crate::util::despawn_components;
 use bevy::ecs::query::QueryEntityError;
@@ -22,7 +24,13 @@ impl Plugin for StationMenuPlugin {
             .add_systems(Update, on_click_exit)
             .add_systems(
                 Update,
-                spawn_station_menu.run_if(on_event::<OnStationClicked>()),
+                on_menu_back
+                    .run_if(in_state(GameState::InGameMenu))
+                    .run_if(on_event::<OnMenuBack>()),
+            )
+            .add_systems(
+                Update,
+                spawn_station_menu.run_if(on_event::<OnStationMenuOpen>()),
             )
             .add_systems(
                 Update,
@@ -35,7 +43,7 @@ const MENU_BG_COLOUR: Color = Color::hsl(227., 0.37, 0.22);
 
 fn spawn_station_menu(
     mut commands: Commands,
-    mut event_reader: EventReader<OnStationClicked>,
+    mut event_reader: EventReader<OnStationMenuOpen>,
     query: Query<&Station>,
     state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
@@ -239,3 +247,11 @@ fn on_click_exit(
         }
     }
 }
+
+fn on_menu_back(
+    mut event_writer: EventWriter<OnStationMenuExit>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    event_writer.send(OnStationMenuExit);
+    next_state.set(GameState::InGame);
+}
@@ -1,6 +1,10 @@
 // Module for common functions
 
 use bevy::prelude::*;
+use std::path::PathBuf;
+
+// Folder name used under the user's config and data directories
+const APP_DIRECTORY: &str = "bevy_prototyping";
 
 pub fn despawn_components<T: Component>(
     mut commands: Commands,
@@ -10,3 +14,11 @@ pub fn despawn_components<T: Component>(
         commands.entity(entity).despawn_recursive();
     }
 }
+
+pub fn data_directory() -> Option<PathBuf> {
+    return dirs::data_dir().map(|directory| directory.join(APP_DIRECTORY));
+}
+
+pub fn config_directory() -> Option<PathBuf> {
+    return dirs::config_dir().map(|directory| directory.join(APP_DIRECTORY));
+}
@@ -0,0 +1,183 @@
+use crate::movement::{TransformInterpolation, Velocity};
+use crate::state::GameState;
+use bevy::prelude::*;
+
+const WORLD_HALF_SIZE: Vec2 = Vec2::new(2000.0, 2000.0);
+const PUSH_BACK_STRENGTH: f32 = 4.0;
+const BOUNDARY_COLOUR: Color = Color::rgba(0.9, 0.3, 0.3, 0.6);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeBehaviour {
+    // Leave one side and come back in on the other, like Asteroids
+    Wrap,
+    // Soft force back towards the bounds, stronger the further out the entity is
+    PushBack,
+    // Stop dead at the edge
+    Clamp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsClass {
+    Player,
+    Enemy,
+    Projectile,
+}
+
+#[derive(Resource, Debug)]
+pub struct WorldBounds {
+    pub half_size: Vec2,
+    pub push_back_strength: f32,
+    pub player: EdgeBehaviour,
+    pub enemy: EdgeBehaviour,
+    pub projectile: EdgeBehaviour,
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self {
+            half_size: WORLD_HALF_SIZE,
+            push_back_strength: PUSH_BACK_STRENGTH,
+            player: EdgeBehaviour::PushBack,
+            enemy: EdgeBehaviour::Wrap,
+            projectile: EdgeBehaviour::Wrap,
+        }
+    }
+}
+
+impl WorldBounds {
+    pub fn behaviour(&self, class: BoundsClass) -> EdgeBehaviour {
+        return match class {
+            BoundsClass::Player => self.player,
+            BoundsClass::Enemy => self.enemy,
+            BoundsClass::Projectile => self.projectile,
+        };
+    }
+
+    pub fn contains(&self, position: Vec2) -> bool {
+        return position.abs().cmple(self.half_size).all();
+    }
+
+    // Called by `movement::update_position` after an entity has moved,
+    // returns an event if the entity has just left the bounds
+    pub fn apply(
+        &self,
+        entity: Entity,
+        bounded: &mut Bounded,
+        transform: &mut Transform,
+        velocity: &mut Velocity,
+        interpolation: Option<Mut<TransformInterpolation>>,
+        delta_seconds: f32,
+    ) -> Option<OnLeftWorldBounds> {
+        let position: Vec2 = transform.translation.truncate();
+        let behaviour: EdgeBehaviour = self.behaviour(bounded.class);
+        let mut event: Option<OnLeftWorldBounds> = None;
+
+        if self.contains(position) {
+            // a clamped entity held against the edge hasn't come back in until it moves off it
+            let held_at_edge: bool =
+                behaviour == EdgeBehaviour::Clamp && position.abs().cmpeq(self.half_size).any();
+
+            if !held_at_edge {
+                bounded.outside = false;
+            }
+
+            return event;
+        }
+
+        if !bounded.outside {
+            event = Some(OnLeftWorldBounds {
+                entity,
+                class: bounded.class,
+            });
+        }
+
+        bounded.outside = true;
+
+        let clamped: Vec2 = position.clamp(-self.half_size, self.half_size);
+
+        match behaviour {
+            EdgeBehaviour::Wrap => {
+                let size: Vec2 = self.half_size * 2.0;
+                let wrapped: Vec2 = (position + self.half_size).rem_euclid(size) - self.half_size;
+                let offset: Vec3 = (wrapped - position).extend(0.0);
+
+                transform.translation += offset;
+
+                // move the previous position too so it isn't drawn sliding across the world
+                if let Some(mut interpolation) = interpolation {
+                    interpolation.previous.translation += offset;
+                }
+
+                bounded.outside = false;
+            }
+            EdgeBehaviour::PushBack => {
+                let penetration: Vec2 = clamped - position;
+                velocity.value +=
+                    (penetration * self.push_back_strength * delta_seconds).extend(0.0);
+            }
+            EdgeBehaviour::Clamp => {
+                transform.translation = clamped.extend(transform.translation.z);
+
+                // stop any movement further out of bounds
+                if clamped.x != position.x {
+                    velocity.value.x = 0.0;
+                }
+
+                if clamped.y != position.y {
+                    velocity.value.y = 0.0;
+                }
+            }
+        }
+
+        return event;
+    }
+}
+
+// Entities with this component are kept inside the `WorldBounds`
+#[derive(Component, Debug)]
+pub struct Bounded {
+    pub class: BoundsClass,
+    outside: bool,
+}
+
+impl Bounded {
+    pub fn new(class: BoundsClass) -> Self {
+        Self {
+            class,
+            outside: false,
+        }
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct OnLeftWorldBounds {
+    pub entity: Entity,
+    pub class: BoundsClass,
+}
+
+pub struct WorldBoundsPlugin;
+
+impl Plugin for WorldBoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorldBounds>()
+            .add_event::<OnLeftWorldBounds>()
+            .add_systems(
+                Update,
+                draw_world_bounds.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(
+                Update,
+                on_left_world_bounds.run_if(on_event::<OnLeftWorldBounds>()),
+            );
+    }
+}
+
+fn draw_world_bounds(mut gizmos: Gizmos, bounds: Res<WorldBounds>) {
+    gizmos.rect_2d(Vec2::ZERO, 0.0, bounds.half_size * 2.0, BOUNDARY_COLOUR);
+}
+
+fn on_left_world_bounds(mut event_reader: EventReader<OnLeftWorldBounds>) {
+    for event in event_reader.read() {
+        info!("{:?} {:?} left the world bounds", event.class, event.entity);
+    }
+}